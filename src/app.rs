@@ -32,6 +32,14 @@ pub fn build() -> App<'static, 'static> {
                 .number_of_values(1)
                 .help("When to use terminal colours"),
         )
+        .arg(
+            Arg::with_name("theme")
+                .long("theme")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("name")
+                .help("Use the color theme read from the file themes/<name>.yaml of the config directory"),
+        )
         .arg(
             Arg::with_name("icon")
                 .long("icon")
@@ -53,6 +61,14 @@ pub fn build() -> App<'static, 'static> {
                 .number_of_values(1)
                 .help("Whether to use fancy or unicode icons"),
         )
+        .arg(
+            Arg::with_name("icon-file")
+                .long("icon-file")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("path")
+                .help("Read additional icon mappings from the given file [default: icons.yaml in the config directory]"),
+        )
         .arg(
             Arg::with_name("indicators")
                 .short("F")
@@ -74,6 +90,52 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Display one entry per line"),
         )
+        .arg(
+            Arg::with_name("quoting-style")
+                .long("quoting-style")
+                .possible_value("literal")
+                .possible_value("shell")
+                .possible_value("shell-always")
+                .possible_value("shell-escape")
+                .possible_value("c")
+                .possible_value("escape")
+                .multiple(true)
+                .number_of_values(1)
+                .help("How to quote the file names (default: shell-escape on a terminal, literal otherwise)"),
+        )
+        .arg(
+            Arg::with_name("hyperlink")
+                .long("hyperlink")
+                .possible_value("always")
+                .possible_value("auto")
+                .possible_value("never")
+                .default_value("auto")
+                .multiple(true)
+                .number_of_values(1)
+                .help("When to make the file names clickable links"),
+        )
+        .arg(
+            Arg::with_name("print0")
+                .short("0")
+                .long("print0")
+                .multiple(true)
+                .help("Print only the names, each ended by a NUL character instead of a newline, without colors, icons or grid (for use with `xargs -0`)"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .possible_value("text")
+                .possible_value("json")
+                .possible_value("ndjson")
+                .possible_value("csv")
+                .possible_value("tsv")
+                .possible_value("html")
+                .possible_value("markdown")
+                .default_value("text")
+                .multiple(true)
+                .number_of_values(1)
+                .help("How to format the output"),
+        )
         .arg(
             Arg::with_name("recursive")
                 .short("R")
@@ -88,6 +150,20 @@ pub fn build() -> App<'static, 'static> {
                 .long("human-readable")
                 .help("For ls compatibility purposes ONLY, currently set by default"),
         )
+        .arg(
+            Arg::with_name("across")
+                .short("x")
+                .long("across")
+                .multiple(true)
+                .help("Display the grid by lines instead of by columns"),
+        )
+        .arg(
+            Arg::with_name("commas")
+                .short("m")
+                .long("commas")
+                .multiple(true)
+                .help("Display the entries separated by commas, filling the width of the terminal"),
+        )
         .arg(
             Arg::with_name("tree")
                 .long("tree")
@@ -125,11 +201,48 @@ pub fn build() -> App<'static, 'static> {
                 .number_of_values(1)
                 .help("How to display size"),
         )
+        .arg(
+            Arg::with_name("size-kind")
+                .long("size-kind")
+                .possible_value("apparent")
+                .possible_value("allocated")
+                .default_value("apparent")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Whether to sort the sizes by length or by allocated disk space (the disk-usage block shows the allocated one)"),
+        )
+        .arg(
+            Arg::with_name("block-size")
+                .long("block-size")
+                .validator(validate_block_size)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("SIZE")
+                .help("Scale the block counts by SIZE (e.g. 512, 4K, 1M or 1MB) [default: 1K]"),
+        )
+        .arg(
+            Arg::with_name("no-total")
+                .long("no-total")
+                .multiple(true)
+                .help("Do not display the total number of blocks of the directories in the long view"),
+        )
         .arg(
             Arg::with_name("total-size")
                 .long("total-size")
                 .multiple(true)
-                .help("Display the total size of directories"),
+                .help("Display the total size of directories (not available with `--format ndjson`)"),
+        )
+        .arg(
+            Arg::with_name("header")
+                .long("header")
+                .multiple(true)
+                .help("Display a title row above the columns of the long view and the tree"),
+        )
+        .arg(
+            Arg::with_name("summary")
+                .long("summary")
+                .multiple(true)
+                .help("Display the number of files, directories and symlinks and their total size after each directory"),
         )
         .arg(
             Arg::with_name("date")
@@ -140,12 +253,24 @@ pub fn build() -> App<'static, 'static> {
                 .number_of_values(1)
                 .help("How to display date [possible values: date, relative, +date-time-format]"),
         )
+        .arg(
+            Arg::with_name("time")
+                .long("time")
+                .possible_value("modified")
+                .possible_value("accessed")
+                .possible_value("changed")
+                .possible_value("created")
+                .default_value("modified")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Which timestamp to display in the date block and to sort by"),
+        )
         .arg(
             Arg::with_name("timesort")
                 .short("t")
                 .long("timesort")
                 .multiple(true)
-                .help("Sort by time modified"),
+                .help("Sort by time (see --time)"),
         )
         .arg(
             Arg::with_name("sizesort")
@@ -154,6 +279,12 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Sort by size"),
         )
+        .arg(
+            Arg::with_name("linksort")
+                .long("linksort")
+                .multiple(true)
+                .help("Sort by number of hard links"),
+        )
         .arg(
             Arg::with_name("reverse")
                 .short("r")
@@ -186,9 +317,32 @@ pub fn build() -> App<'static, 'static> {
                     "date",
                     "name",
                     "inode",
+                    "links",
+                    "disk-usage",
+                    "octal",
+                    "uid",
+                    "gid",
+                    "acl",
+                    "context",
+                    "attributes",
+                    "capabilities",
+                    "atime",
+                    "ctime",
+                    "btime",
                 ])
                 .help("Specify the blocks that will be displayed and in what order"),
         )
+        .arg(
+            Arg::with_name("permission")
+                .long("permission")
+                .possible_value("rwx")
+                .possible_value("octal")
+                .possible_value("attributes")
+                .default_value("rwx")
+                .multiple(true)
+                .number_of_values(1)
+                .help("How to display the permissions (attributes: the file attributes on Windows, the inode flags shown by lsattr instead of rwx on Linux, rwx elsewhere)"),
+        )
         .arg(
             Arg::with_name("classic")
                 .long("classic")
@@ -217,9 +371,89 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Display the index number of each file"),
         )
+        .arg(
+            Arg::with_name("context")
+                .short("Z")
+                .long("context")
+                .multiple(true)
+                .help("Display the SELinux security context of each file"),
+        )
+        .arg(
+            Arg::with_name("numeric-uid-gid")
+                .short("n")
+                .long("numeric-uid-gid")
+                .multiple(true)
+                .help("Like --long, but display the numeric user and group IDs instead of the names"),
+        )
+        .arg(
+            Arg::with_name("xattr")
+                .short("@")
+                .long("xattr")
+                .multiple(true)
+                .help("List the extended attributes and the size of their value under each entry of the long view and the tree"),
+        )
+        .arg(
+            Arg::with_name("links")
+                .long("links")
+                .multiple(true)
+                .help("Display the number of hard links of each file in the long view"),
+        )
+        .arg(
+            Arg::with_name("config-file")
+                .long("config-file")
+                .takes_value(true)
+                .value_name("path")
+                .conflicts_with("ignore-config")
+                .help("Read the configuration from the given file instead of the default one"),
+        )
+        .arg(
+            Arg::with_name("ignore-config")
+                .long("ignore-config")
+                .help("Do not read any configuration file"),
+        )
+}
+
+/// Parse a block size as GNU ls does: an optional number followed by an
+/// optional unit, `K`, `M`... or `KiB`, `MiB`... for powers of 1024, and
+/// `KB`, `MB`... for powers of 1000.
+pub fn parse_block_size(arg: &str) -> Option<u64> {
+    let unit_start = arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len());
+    let (number, unit) = arg.split_at(unit_start);
+
+    let number = if number.is_empty() {
+        1
+    } else {
+        number.parse::<u64>().ok()?
+    };
+
+    let mut chars = unit.chars();
+    let multiplier = match chars.next() {
+        None => 1,
+        Some(prefix) => {
+            let exponent = "KMGTPE".find(prefix.to_ascii_uppercase())? as u32 + 1;
+            let base: u64 = match chars.as_str() {
+                "" | "iB" => 1024,
+                "B" => 1000,
+                _ => return None,
+            };
+            base.checked_pow(exponent)?
+        }
+    };
+
+    match number.checked_mul(multiplier) {
+        Some(0) | None => None,
+        size => size,
+    }
+}
+
+fn validate_block_size(arg: String) -> Result<(), String> {
+    match parse_block_size(&arg) {
+        Some(_) => Ok(()),
+        None => Err(format!("invalid block size: {}", arg)),
+    }
 }
 
-fn validate_date_argument(arg: String) -> Result<(), String> {
+pub fn validate_date_argument(arg: String) -> Result<(), String> {
     if arg.starts_with('+') {
         validate_time_format(&arg).map_err(|err| err.to_string())
     } else if &arg == "date" || &arg == "relative" {
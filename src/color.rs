@@ -1,3 +1,4 @@
+use crate::theme::ColorTheme;
 use ansi_term::{ANSIString, Colour, Style};
 use lscolors::{Indicator, LsColors};
 use std::collections::HashMap;
@@ -48,6 +49,29 @@ pub enum Elem {
     INode {
         valid: bool,
     },
+
+    /// Permission bits in octal
+    Octal,
+
+    /// Extended attributes
+    Xattr,
+
+    /// POSIX ACL
+    Acl,
+
+    /// SELinux security context
+    Context,
+
+    /// Permissions of the immutable files
+    Immutable,
+
+    /// Hard link count
+    Links {
+        multiple: bool,
+    },
+
+    /// Column titles
+    Header,
 }
 
 impl Elem {
@@ -62,29 +86,42 @@ impl Elem {
 pub type ColoredString<'a> = ANSIString<'a>;
 
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum Theme {
     NoColor,
     Default,
     NoLscolors,
+    /// The default colors overridden by a theme file. `LS_COLORS` is ignored.
+    Custom(ColorTheme),
 }
 
 pub struct Colors {
-    colors: Option<HashMap<Elem, Colour>>,
+    colors: Option<HashMap<Elem, Style>>,
     lscolors: Option<LsColors>,
 }
 
 impl Colors {
     pub fn new(theme: Theme) -> Self {
-        let colors = match theme {
+        let colors = match &theme {
             Theme::NoColor => None,
-            Theme::Default => Some(Self::get_light_theme_colour_map()),
-            Theme::NoLscolors => Some(Self::get_light_theme_colour_map()),
+            Theme::Default => Some(Self::get_light_theme_style_map()),
+            Theme::NoLscolors => Some(Self::get_light_theme_style_map()),
+            Theme::Custom(custom) => {
+                let mut m = Self::get_light_theme_style_map();
+                m.extend(
+                    custom
+                        .styles()
+                        .iter()
+                        .map(|(elem, style)| (elem.clone(), *style)),
+                );
+                Some(m)
+            }
         };
         let lscolors = match theme {
             Theme::NoColor => None,
             Theme::Default => Some(LsColors::from_env().unwrap_or_default()),
             Theme::NoLscolors => None,
+            Theme::Custom(_) => None,
         };
 
         Self { colors, lscolors }
@@ -133,11 +170,11 @@ impl Colors {
 
     fn style_default(&self, elem: &Elem) -> Style {
         if let Some(ref colors) = self.colors {
-            let style_fg = Style::default().fg(colors[elem]);
-            if elem.has_suid() {
-                style_fg.on(Colour::Fixed(124)) // Red3
+            let style = colors[elem];
+            if elem.has_suid() && style.background.is_none() {
+                style.on(Colour::Fixed(124)) // Red3
             } else {
-                style_fg
+                style
             }
         } else {
             Style::default()
@@ -177,6 +214,18 @@ impl Colors {
         }
     }
 
+    fn get_light_theme_style_map() -> HashMap<Elem, Style> {
+        let mut m: HashMap<Elem, Style> = Self::get_light_theme_colour_map()
+            .into_iter()
+            .map(|(elem, colour)| (elem, Style::default().fg(colour)))
+            .collect();
+
+        // Column titles
+        m.insert(Elem::Header, Style::default().underline());
+
+        m
+    }
+
     // You can find the table for each color, code, and display at:
     //
     //https://jonasjacek.github.io/colors/
@@ -247,6 +296,25 @@ impl Colors {
         m.insert(Elem::INode { valid: true }, Colour::Fixed(13)); // Pink
         m.insert(Elem::INode { valid: false }, Colour::Fixed(7)); // Grey
 
+        // Octal permissions
+        m.insert(Elem::Octal, Colour::Fixed(6)); // DarkTurquoise
+
+        // Extended attributes
+        m.insert(Elem::Xattr, Colour::Fixed(14)); // Aqua
+
+        // POSIX ACL
+        m.insert(Elem::Acl, Colour::Fixed(11)); // Yellow
+
+        // SELinux security context
+        m.insert(Elem::Context, Colour::Fixed(13)); // Fuchsia
+
+        // Permissions of the immutable files
+        m.insert(Elem::Immutable, Colour::Fixed(9)); // Red
+
+        // Hard link count
+        m.insert(Elem::Links { multiple: false }, Colour::Fixed(7)); // Grey
+        m.insert(Elem::Links { multiple: true }, Colour::Fixed(11)); // Yellow
+
         m
     }
 }
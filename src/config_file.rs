@@ -0,0 +1,217 @@
+use crate::flags::{
+    Block, DirOrderFlag, Display, Format, IconTheme, Layout, PermissionFlag, QuotingStyle,
+    SizeFlag, SizeKind, SortFlag, TimeFlag, WhenFlag,
+};
+use crate::print_error;
+use clap::ArgMatches;
+use serde::Deserialize;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// The file names looked up in the configuration directory, by order of
+/// preference.
+const CONF_FILE_NAMES: [&str; 3] = ["config.yaml", "config.yml", "config.toml"];
+
+/// The content of a configuration file.
+///
+/// Every key mirrors the long name of a command line flag and is optional: a
+/// missing key keeps the built-in default, and any flag given on the command
+/// line takes precedence over the value read here.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub classic: Option<bool>,
+    pub blocks: Option<Vec<Block>>,
+    pub permission: Option<PermissionFlag>,
+    pub color: Option<WhenFlag>,
+    pub theme: Option<String>,
+    pub icon: Option<WhenFlag>,
+    pub icon_theme: Option<IconTheme>,
+    pub icon_file: Option<String>,
+    pub date: Option<String>,
+    pub time: Option<TimeFlag>,
+    pub size: Option<SizeFlag>,
+    pub size_kind: Option<SizeKind>,
+    pub layout: Option<Layout>,
+    pub format: Option<Format>,
+    pub display: Option<Display>,
+    pub recursive: Option<bool>,
+    pub depth: Option<usize>,
+    pub sort: Option<SortFlag>,
+    pub reverse: Option<bool>,
+    pub group_dirs: Option<DirOrderFlag>,
+    pub indicators: Option<bool>,
+    pub no_symlink: Option<bool>,
+    pub total_size: Option<bool>,
+    pub block_size: Option<String>,
+    pub no_total: Option<bool>,
+    pub header: Option<bool>,
+    pub summary: Option<bool>,
+    pub print0: Option<bool>,
+    pub quoting_style: Option<QuotingStyle>,
+    pub hyperlink: Option<WhenFlag>,
+    pub inode: Option<bool>,
+    pub context: Option<bool>,
+    pub numeric_uid_gid: Option<bool>,
+    pub xattr: Option<bool>,
+    pub links: Option<bool>,
+    pub ignore_globs: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Load the configuration selected by the `--config-file` and
+    /// `--ignore-config` flags.
+    ///
+    /// A file which can't be read or parsed is reported on stderr and the
+    /// built-in defaults are used instead.
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        if matches.is_present("ignore-config") {
+            return Self::default();
+        }
+
+        let path = match matches.value_of("config-file") {
+            Some(path) => PathBuf::from(path),
+            None => match Self::default_path() {
+                Some(path) => path,
+                None => return Self::default(),
+            },
+        };
+
+        match Self::from_file(&path) {
+            Ok(config) => config,
+            Err(err) => {
+                print_error!("lsd: {}: {}\n", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    /// Read and parse a configuration file. The format is deduced from the
+    /// extension: `.toml` files are parsed as TOML, everything else as YAML.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            _ => Self::from_yaml(&content),
+        }
+    }
+
+    fn from_yaml(content: &str) -> Result<Self, Error> {
+        // An empty YAML document is a valid, empty configuration.
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(content).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    fn from_toml(content: &str) -> Result<Self, Error> {
+        toml::from_str(content).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Return the path of the first configuration file found in the lsd
+    /// configuration directory.
+    fn default_path() -> Option<PathBuf> {
+        CONF_FILE_NAMES
+            .iter()
+            .find_map(|name| find_config_file(name))
+    }
+}
+
+/// Look for a file in the lsd configuration directory: `$XDG_CONFIG_HOME/lsd`
+/// on unix.
+#[cfg(unix)]
+pub fn find_config_file(name: &str) -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("lsd")
+        .ok()?
+        .find_config_file(name)
+}
+
+/// Look for a file in the lsd configuration directory: `%APPDATA%\lsd` on
+/// windows.
+#[cfg(windows)]
+pub fn find_config_file(name: &str) -> Option<PathBuf> {
+    let path = dirs::config_dir()?.join("lsd").join(name);
+
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Config;
+    use crate::flags::{Block, Display, Layout, SortFlag, WhenFlag};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_yaml() {
+        let config = Config::from_yaml(
+            r#"
+color: never
+blocks:
+  - permission
+  - name
+layout: tree
+display: almost-all
+sort: time
+ignore-globs:
+  - "*.o"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(Some(WhenFlag::Never), config.color);
+        assert_eq!(Some(vec![Block::Permission, Block::Name]), config.blocks);
+        assert_eq!(Some(Layout::Tree), config.layout);
+        assert_eq!(Some(Display::DisplayAlmostAll), config.display);
+        assert_eq!(Some(SortFlag::Time), config.sort);
+        assert_eq!(Some(vec!["*.o".to_string()]), config.ignore_globs);
+        assert_eq!(None, config.icon);
+    }
+
+    #[test]
+    fn test_read_toml() {
+        let config = Config::from_toml(
+            r#"
+total-size = true
+depth = 2
+blocks = ["size", "name"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(Some(true), config.total_size);
+        assert_eq!(Some(2), config.depth);
+        assert_eq!(Some(vec![Block::Size, Block::Name]), config.blocks);
+    }
+
+    #[test]
+    fn test_read_empty_yaml() {
+        assert_eq!(Config::default(), Config::from_yaml("").unwrap());
+    }
+
+    #[test]
+    fn test_read_invalid_value() {
+        assert!(Config::from_yaml("color: sometimes").is_err());
+        assert!(Config::from_yaml("unknown-key: true").is_err());
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let toml_path = tmp_dir.path().join("config.toml");
+        fs::write(&toml_path, "inode = true\n").expect("failed to write config");
+        assert_eq!(Some(true), Config::from_file(&toml_path).unwrap().inode);
+
+        let yaml_path = tmp_dir.path().join("config.yaml");
+        fs::write(&yaml_path, "inode: true\n").expect("failed to write config");
+        assert_eq!(Some(true), Config::from_file(&yaml_path).unwrap().inode);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::color::{self, Colors};
 use crate::display;
-use crate::flags::{Display, Flags, IconTheme, Layout, WhenFlag};
+use crate::flags::{Display, Flags, Format, IconTheme, Layout, QuotingStyle, WhenFlag};
+use crate::format;
 use crate::icon::{self, Icons};
 use crate::meta::Meta;
+use crate::theme::{ColorTheme, IconSet};
 use crate::{print_error, print_output, sort};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(not(target_os = "windows"))]
 use std::io;
@@ -22,7 +24,7 @@ pub struct Core {
 }
 
 impl Core {
-    pub fn new(flags: Flags) -> Self {
+    pub fn new(mut flags: Flags) -> Self {
         // Check through libc if stdout is a tty. Unix specific so not on windows.
         // Determine color output availability (and initialize color output (for Windows 10))
         #[cfg(not(target_os = "windows"))]
@@ -37,19 +39,71 @@ impl Core {
         #[cfg(target_os = "windows")]
         let console_color_ok = ansi_term::enable_ansi_support().is_ok();
 
+        if flags.quoting_style.is_none()
+            && tty_available
+            && !flags.print0
+            && flags.format == Format::Text
+        {
+            // As with `ls`, the names are quoted only for the terminal.
+            flags.quoting_style = Some(QuotingStyle::ShellEscape);
+        }
+
+        flags.hyperlink = match (flags.hyperlink, tty_available) {
+            // The links only make sense in the names written to a terminal.
+            _ if flags.print0 || flags.format != Format::Text => WhenFlag::Never,
+            (WhenFlag::Auto, true) => WhenFlag::Always,
+            (WhenFlag::Auto, false) => WhenFlag::Never,
+            (when, _) => when,
+        };
+
         let mut inner_flags = flags.clone();
 
-        let color_theme = match (tty_available && console_color_ok, flags.color) {
+        // The HTML output keeps the colors even when written to a file.
+        let color_available = (tty_available && console_color_ok) || flags.format == Format::Html;
+
+        let color_theme = match (color_available, flags.color) {
+            _ if flags.print0 => color::Theme::NoColor,
             (_, WhenFlag::Never) | (false, WhenFlag::Auto) => color::Theme::NoColor,
-            _ => color::Theme::Default,
+            _ => match &flags.color_theme {
+                Some(name) => match ColorTheme::from_name(name) {
+                    Ok(theme) => color::Theme::Custom(theme),
+                    Err(err) => {
+                        print_error!("lsd: {}\n", err);
+                        color::Theme::Default
+                    }
+                },
+                None => color::Theme::Default,
+            },
+        };
+
+        // The entries only read what colors their names when they are shown
+        // with colors.
+        flags.color = match color_theme {
+            color::Theme::NoColor => WhenFlag::Never,
+            _ => WhenFlag::Always,
         };
 
         let icon_theme = match (tty_available, flags.icon, flags.icon_theme) {
+            _ if flags.print0 => icon::Theme::NoIcon,
             (_, WhenFlag::Never, _) | (false, WhenFlag::Auto, _) => icon::Theme::NoIcon,
             (_, _, IconTheme::Fancy) => icon::Theme::Fancy,
             (_, _, IconTheme::Unicode) => icon::Theme::Unicode,
         };
 
+        let icon_set = match (icon_theme, &flags.icon_file) {
+            (icon::Theme::NoIcon, _) => Ok(None),
+            (_, Some(path)) => IconSet::from_file(Path::new(path)).map(Some),
+            (_, None) => IconSet::from_config_dir(),
+        };
+        let icons = match icon_set {
+            Ok(Some(set)) => Icons::new(icon_theme).with_icon_set(set),
+            Ok(None) => Icons::new(icon_theme),
+            Err(err) => {
+                print_error!("lsd: icon file: {}\n", err);
+                Icons::new(icon_theme)
+            }
+        };
+
         if !tty_available {
             // The output is not a tty, this means the command is piped. (ex: lsd -l | less)
             //
@@ -62,11 +116,17 @@ impl Core {
             flags,
             //display: Display::new(inner_flags),
             colors: Colors::new(color_theme),
-            icons: Icons::new(icon_theme),
+            icons,
         }
     }
 
     pub fn run(self, paths: Vec<PathBuf>) {
+        if self.flags.format == Format::Ndjson {
+            // Stream the entries instead of loading the whole tree.
+            format::ndjson::stream(paths, &self.flags);
+            return;
+        }
+
         let mut meta_list = self.fetch(paths);
 
         self.sort(&mut meta_list);
@@ -83,7 +143,7 @@ impl Core {
 
         for path in paths {
             let mut meta = match Meta::from_path(&path) {
-                Ok(meta) => meta,
+                Ok(meta) => meta.with_details(&self.flags),
                 Err(err) => {
                     print_error!("lsd: {}: {}\n", path.display(), err);
                     continue;
@@ -95,7 +155,7 @@ impl Core {
                     meta_list.push(meta);
                 }
                 _ => {
-                    match meta.recurse_into(depth, self.flags.display, &self.flags.ignore_globs) {
+                    match meta.recurse_into(depth, &self.flags) {
                         Ok(content) => {
                             meta.content = content;
                             meta_list.push(meta);
@@ -128,10 +188,20 @@ impl Core {
     }
 
     fn display(&self, metas: &[Meta]) {
-        let output = if self.flags.layout == Layout::Tree {
-            display::tree(&metas, &self.flags, &self.colors, &self.icons)
-        } else {
-            display::grid(&metas, &self.flags, &self.colors, &self.icons)
+        let output = match (self.flags.format, self.flags.layout) {
+            (Format::Json, _) => format::json::render(metas, &self.flags),
+            (Format::Ndjson, _) => unreachable!("ndjson output is streamed"),
+            (Format::Csv, _) => format::csv::render(metas, &self.flags, ','),
+            (Format::Tsv, _) => format::csv::render(metas, &self.flags, '\t'),
+            (Format::Html, _) => {
+                format::html::render(metas, &self.flags, &self.colors, &self.icons)
+            }
+            (Format::Markdown, _) => format::markdown::render(metas, &self.flags, &self.icons),
+            // The NUL-separated entries have no tree decoration.
+            (Format::Text, Layout::Tree) if !self.flags.print0 => {
+                display::tree(&metas, &self.flags, &self.colors, &self.icons)
+            }
+            (Format::Text, _) => display::grid(&metas, &self.flags, &self.colors, &self.icons),
         };
 
         print_output!("{}", output);
@@ -1,10 +1,12 @@
-use crate::color::{ColoredString, Colors};
-use crate::flags::{Block, Display, Flags, Layout};
+use crate::color::{ColoredString, Colors, Elem, Theme};
+use crate::flags::{Block, Display, Flags, Layout, PermissionFlag};
 use crate::icon::Icons;
 use crate::meta::name::DisplayOption;
-use crate::meta::{FileType, Meta};
+use crate::meta::{Date, FileType, Meta, Size};
+use crate::summary::Summary;
 use ansi_term::{ANSIString, ANSIStrings};
 use std::collections::HashMap;
+use std::ops::Index;
 use term_grid::{Cell, Direction, Filling, Grid, GridOptions};
 use terminal_size::terminal_size;
 use unicode_width::UnicodeWidthStr;
@@ -13,6 +15,7 @@ const EDGE: &str = "\u{251c}\u{2500}\u{2500}"; // "├──"
 const LINE: &str = "\u{2502}  "; // "├  "
 const CORNER: &str = "\u{2514}\u{2500}\u{2500}"; // "└──"
 const BLANK: &str = "   ";
+const SPARSE_MARKER: &str = "~"; // after the disk usage of the sparse files
 
 pub fn grid(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
     let term_width = match terminal_size() {
@@ -20,7 +23,7 @@ pub fn grid(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> St
         None => None,
     };
 
-    inner_display_grid(
+    let mut output = inner_display_grid(
         &DisplayOption::None,
         metas,
         &flags,
@@ -28,11 +31,29 @@ pub fn grid(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> St
         icons,
         0,
         term_width,
-    )
+    );
+
+    // Each listed directory has its own footer, so the grand total is only
+    // useful when several of them are listed.
+    if flags.summary && !flags.print0 && (flags.recursive || metas.len() > 1) {
+        output += &display_total(metas, flags);
+    }
+
+    output
 }
 
 pub fn tree(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
-    inner_display_tree(metas, &flags, colors, icons, 0, "")
+    let mut output = inner_display_tree(metas, &flags, colors, icons, 0, "");
+
+    if flags.summary {
+        output += &display_total(metas, flags);
+    }
+
+    output
+}
+
+fn display_total(metas: &[Meta], flags: &Flags) -> String {
+    format!("\nTotal: {}", Summary::total(metas, flags).render(flags))
 }
 
 fn inner_display_grid(
@@ -46,12 +67,16 @@ fn inner_display_grid(
 ) -> String {
     let mut output = String::new();
 
-    let padding_rules = get_padding_rules(&metas, flags);
+    let padding_rules = get_padding_rules(&metas, flags, display_option);
     let mut grid = match flags.layout {
         Layout::OneLine => Grid::new(GridOptions {
             filling: Filling::Spaces(1),
             direction: Direction::LeftToRight,
         }),
+        Layout::Across => Grid::new(GridOptions {
+            filling: Filling::Spaces(2),
+            direction: Direction::LeftToRight,
+        }),
         _ => Grid::new(GridOptions {
             filling: Filling::Spaces(2),
             direction: Direction::TopToBottom,
@@ -63,6 +88,35 @@ fn inner_display_grid(
     // asked to display the directory itself (rather than its contents).
     let skip_dirs = (depth == 0) && (flags.display != Display::DisplayDirectoryItself);
 
+    let has_entries = metas.iter().any(|meta| match meta.file_type {
+        FileType::Directory { .. } => !skip_dirs,
+        _ => true,
+    });
+    // The extended attributes are listed under each row of the long view.
+    let with_xattrs = flags.xattr && flags.layout == Layout::OneLine && !flags.print0;
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    let mut row_metas: Vec<&Meta> = Vec::new();
+
+    if flags.header && flags.layout == Layout::OneLine && !flags.print0 && has_entries {
+        let cells = header_cells(flags, colors, &padding_rules);
+        if with_xattrs {
+            rows.push(cells);
+        } else {
+            for cell in cells {
+                grid.add(cell);
+            }
+        }
+    }
+
+    // Like `ls -l`, start the listing of a directory with the total number
+    // of blocks allocated to its entries.
+    if flags.block_total && !flags.print0 && depth > 0 {
+        output += &display_block_total(metas, flags);
+    }
+
+    // The entries of the comma-separated layout, with their width.
+    let mut entries: Vec<(String, usize)> = Vec::new();
+
     // print the files first.
     for meta in metas {
         // Maybe skip showing the directory meta now; show its contents later.
@@ -70,6 +124,11 @@ fn inner_display_grid(
             continue;
         }
 
+        if flags.print0 {
+            output += &get_print0_entry(meta, flags, display_option);
+            continue;
+        }
+
         let blocks = get_output(
             &meta,
             &colors,
@@ -79,17 +138,42 @@ fn inner_display_grid(
             &padding_rules,
         );
 
-        for block in blocks {
+        if flags.layout == Layout::Commas {
+            let entry = blocks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(" ");
+            let width = get_visible_width(&entry);
+            entries.push((entry, width));
+            continue;
+        }
+
+        let cells = blocks.iter().map(|block| {
             let block_str = block.to_string();
 
-            grid.add(Cell {
+            Cell {
                 width: get_visible_width(&block_str),
                 contents: block_str,
-            });
+            }
+        });
+
+        if with_xattrs {
+            rows.push(cells.collect());
+            row_metas.push(meta);
+        } else {
+            for cell in cells {
+                grid.add(cell);
+            }
         }
     }
 
-    if flags.layout == Layout::Grid {
+    if flags.print0 {
+        // The entries are already written, one by one.
+    } else if flags.layout == Layout::Commas {
+        // Like `ls`, wrap at 80 columns when the width is unknown.
+        output += &display_commas(&entries, term_width.unwrap_or(80));
+    } else if flags.layout == Layout::Grid || flags.layout == Layout::Across {
         if let Some(tw) = term_width {
             if let Some(gridded_output) = grid.fit_into_width(tw) {
                 output += &gridded_output.to_string();
@@ -102,16 +186,37 @@ fn inner_display_grid(
         } else {
             output += &grid.fit_into_columns(1).to_string();
         }
+    } else if with_xattrs {
+        let lines = align_rows(&rows);
+        // The header, if any, is the row without a meta.
+        let header_len = lines.len() - row_metas.len();
+
+        for line in &lines[..header_len] {
+            output += line;
+            output += "\n";
+        }
+        for (line, meta) in lines[header_len..].iter().zip(row_metas) {
+            output += line;
+            output += "\n";
+            output += &display_xattrs(meta, colors, "");
+        }
     } else {
         output += &grid.fit_into_columns(flags.blocks.len()).to_string();
     }
 
+    // The entries below the inputs are the content of a listed directory.
+    if let (true, false, DisplayOption::Relative { base_path }) =
+        (flags.summary, flags.print0, display_option)
+    {
+        output += &Summary::from_content(base_path, metas, flags).render(flags);
+    }
+
     let should_display_folder_path = should_display_folder_path(depth, &metas);
 
     // print the folder content
     for meta in metas {
         if meta.content.is_some() {
-            if should_display_folder_path {
+            if should_display_folder_path && !flags.print0 {
                 output += &display_folder_path(&meta);
             }
 
@@ -145,33 +250,45 @@ fn inner_display_tree(
     let mut output = String::new();
     let last_idx = metas.len();
 
-    let padding_rules = get_padding_rules(&metas, flags);
+    let padding_rules = get_padding_rules(&metas, flags, &DisplayOption::FileName);
 
-    let mut grid = Grid::new(GridOptions {
-        filling: Filling::Spaces(1),
-        direction: Direction::LeftToRight,
-    });
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+
+    let display_header = flags.header && depth == 0 && !metas.is_empty();
+    if display_header {
+        rows.push(header_cells(flags, colors, &padding_rules));
+    }
 
     for meta in metas.iter() {
-        for block in get_output(
+        let cells = get_output(
             &meta,
             &colors,
             &icons,
             &flags,
             &DisplayOption::FileName,
             &padding_rules,
-        ) {
+        )
+        .iter()
+        .map(|block| {
             let block_str = block.to_string();
 
-            grid.add(Cell {
+            Cell {
                 width: get_visible_width(&block_str),
                 contents: block_str,
-            });
-        }
+            }
+        })
+        .collect();
+
+        rows.push(cells);
     }
 
-    let content = grid.fit_into_columns(flags.blocks.len()).to_string();
-    let mut lines = content.lines();
+    let lines = align_rows(&rows);
+    let mut lines = lines.iter();
+
+    if display_header {
+        output += lines.next().unwrap();
+        output += "\n";
+    }
 
     for (idx, meta) in metas.iter().enumerate() {
         let is_last_folder_elem = idx + 1 != last_idx;
@@ -187,20 +304,27 @@ fn inner_display_tree(
             output += " ";
         }
 
-        output += &String::from(lines.next().unwrap());
+        output += lines.next().unwrap();
         output += "\n";
 
-        if meta.content.is_some() {
-            let mut new_prefix = String::from(prefix);
+        let mut new_prefix = String::from(prefix);
 
-            if depth > 0 {
-                if is_last_folder_elem {
-                    new_prefix += LINE;
-                } else {
-                    new_prefix += BLANK;
-                }
+        if depth > 0 {
+            if is_last_folder_elem {
+                new_prefix += LINE;
+            } else {
+                new_prefix += BLANK;
             }
+        }
 
+        if flags.xattr {
+            let has_content = matches!(&meta.content, Some(content) if !content.is_empty());
+            let xattr_prefix = new_prefix.clone() + if has_content { LINE } else { BLANK };
+
+            output += &display_xattrs(meta, colors, &xattr_prefix);
+        }
+
+        if meta.content.is_some() {
             output += &inner_display_tree(
                 &meta.content.as_ref().unwrap(),
                 &flags,
@@ -215,7 +339,45 @@ fn inner_display_tree(
     output
 }
 
-fn should_display_folder_path(depth: usize, metas: &[Meta]) -> bool {
+/// Return the lines listing the extended attributes of the entry under it,
+/// with the size of their value.
+fn display_xattrs(meta: &Meta, colors: &Colors, prefix: &str) -> String {
+    let attrs = meta.xattrs.attrs();
+    let mut output = String::new();
+
+    for (idx, attr) in attrs.iter().enumerate() {
+        let connector = if idx + 1 == attrs.len() { CORNER } else { EDGE };
+
+        output += &format!(
+            "{}{} {} ({} B)\n",
+            prefix,
+            connector,
+            colors.colorize(attr.name.clone(), &Elem::Xattr),
+            attr.size
+        );
+    }
+
+    output
+}
+
+/// Return an entry of the `--print0` output: the bare name, ended by a NUL
+/// character, without the other blocks, the indicators nor the symlink
+/// targets. The recursive listings use the full paths, as there are no folder
+/// headers to tell where the entries are.
+fn get_print0_entry(meta: &Meta, flags: &Flags, display_option: &DisplayOption) -> String {
+    let display_option = if flags.recursive || flags.layout == Layout::Tree {
+        &DisplayOption::None
+    } else {
+        display_option
+    };
+
+    let mut entry = meta.name.display_text(display_option);
+    entry.push('\0');
+
+    entry
+}
+
+pub fn should_display_folder_path(depth: usize, metas: &[Meta]) -> bool {
     if depth > 0 {
         true
     } else {
@@ -231,6 +393,93 @@ fn should_display_folder_path(depth: usize, metas: &[Meta]) -> bool {
     }
 }
 
+fn display_block_total(metas: &[Meta], flags: &Flags) -> String {
+    let bytes: u64 = metas.iter().map(|meta| meta.blocks.bytes()).sum();
+    let total = ((bytes as f64) / (flags.block_size as f64)).ceil();
+
+    format!("total {}\n", total)
+}
+
+/// Return the titles of the blocks, the first row of the listing. The size
+/// and disk usage titles are right-aligned on the sizes and their units.
+fn header_cells(flags: &Flags, colors: &Colors, padding_rules: &PaddingRules) -> Vec<Cell> {
+    let mut cells = Vec::with_capacity(flags.blocks.len());
+
+    for block in &flags.blocks {
+        let padding = match block {
+            Block::Size | Block::DiskUsage => {
+                padding_rules[block].saturating_sub(block.title(flags.time).len())
+            }
+            _ => 0,
+        };
+        let title = colors.colorize(block.title(flags.time).to_string(), &Elem::Header);
+
+        cells.push(Cell {
+            width: padding + block.title(flags.time).len(),
+            contents: format!("{}{}", " ".repeat(padding), title),
+        });
+    }
+
+    cells
+}
+
+/// Lay the rows out like a one-line grid: the cells padded to the width of
+/// their column, one space apart. Each row is rendered apart, so that lines
+/// can be added under it even when a cell spans several lines.
+fn align_rows(rows: &[Vec<Cell>]) -> Vec<String> {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        for (idx, cell) in row.iter().enumerate() {
+            match widths.get_mut(idx) {
+                Some(width) => *width = (*width).max(cell.width),
+                None => widths.push(cell.width),
+            }
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            let mut line = String::new();
+            for (idx, cell) in row.iter().enumerate() {
+                line += &cell.contents;
+                if idx + 1 < row.len() {
+                    line += &" ".repeat(widths[idx] - cell.width + 1);
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+/// Join the entries with commas, going to the next line before an entry
+/// which would overflow the width.
+fn display_commas(entries: &[(String, usize)], term_width: usize) -> String {
+    let mut output = String::new();
+    let mut line_width = 0;
+
+    for (idx, (entry, width)) in entries.iter().enumerate() {
+        if idx > 0 {
+            // Keep room for the comma ending the line.
+            if line_width + 2 + width + 1 >= term_width {
+                output += ",\n";
+                line_width = 0;
+            } else {
+                output += ", ";
+                line_width += 2;
+            }
+        }
+
+        output += entry;
+        line_width += width;
+    }
+
+    if !entries.is_empty() {
+        output.push('\n');
+    }
+
+    output
+}
+
 fn display_folder_path(meta: &Meta) -> String {
     let mut output = String::new();
     output.push('\n');
@@ -240,47 +489,117 @@ fn display_folder_path(meta: &Meta) -> String {
     output
 }
 
-fn get_output<'a>(
+fn render_date<'a>(date: Option<&'a Date>, colors: &'a Colors, flags: &Flags) -> ColoredString<'a> {
+    match date {
+        Some(date) => date.render(colors, flags),
+        None => Date::render_missing(colors),
+    }
+}
+
+/// Render the permissions in the form given by `--permission`.
+fn render_permissions<'a>(meta: &'a Meta, colors: &'a Colors, flags: &Flags) -> ColoredString<'a> {
+    match flags.permission {
+        PermissionFlag::Rwx => meta.permissions.render(colors),
+        PermissionFlag::Octal => meta.permissions.render_octal(colors),
+        // The file attributes on Windows, the inode flags on Linux.
+        PermissionFlag::Attributes => meta
+            .attributes
+            .render(colors)
+            .or_else(|| meta.inode_flags.render(colors))
+            .unwrap_or_else(|| meta.permissions.render(colors)),
+    }
+}
+
+pub fn get_output<'a>(
     meta: &'a Meta,
     colors: &'a Colors,
     icons: &'a Icons,
     flags: &'a Flags,
     display_option: &DisplayOption,
-    padding_rules: &HashMap<Block, usize>,
+    padding_rules: &PaddingRules,
 ) -> Vec<ANSIString<'a>> {
     let mut strings: Vec<ANSIString> = Vec::new();
     for block in flags.blocks.iter() {
         match block {
             Block::INode => strings.push(meta.inode.render(colors)),
+            Block::Links => strings.push(meta.links.render(colors, padding_rules[&Block::Links])),
             Block::Permission => {
+                // The permissions of the immutable files don't apply, even to
+                // root: they are painted with a single style.
+                let permissions = if meta.inode_flags.is_immutable() {
+                    let no_colors = Colors::new(Theme::NoColor);
+                    let permissions = render_permissions(meta, &no_colors, flags).to_string();
+                    colors.colorize(permissions, &Elem::Immutable)
+                } else {
+                    render_permissions(meta, colors, flags)
+                };
                 let s: &[ColoredString] = &[
                     meta.file_type.render(colors),
-                    meta.permissions.render(colors),
+                    permissions,
+                    meta.acl.render_indicator(colors),
+                    meta.xattrs.render_indicator(colors),
                 ];
                 let res = ANSIStrings(s).to_string();
                 strings.push(ColoredString::from(res));
             }
-            Block::User => strings.push(meta.owner.render_user(colors)),
-            Block::Group => strings.push(meta.owner.render_group(colors)),
-            Block::Size => strings.push(meta.size.render(
-                colors,
-                &flags,
-                padding_rules[&Block::SizeValue],
-            )),
+            Block::Octal => strings.push(meta.permissions.render_octal(colors)),
+            Block::User => strings.push(meta.owner.render_user(colors, flags)),
+            Block::Group => strings.push(meta.owner.render_group(colors, flags)),
+            Block::Uid => strings.push(meta.owner.render_uid(colors)),
+            Block::Gid => strings.push(meta.owner.render_gid(colors)),
+            Block::Acl => strings.push(meta.acl.render(colors, flags)),
+            Block::Context => strings.push(meta.context.render(colors)),
+            Block::Attributes => strings.push(meta.inode_flags.render_block(colors)),
+            Block::Capabilities => strings.push(meta.capabilities.render(colors)),
+            Block::Size => match meta.device.numbers() {
+                Some(_) => strings.push(meta.device.render(
+                    colors,
+                    padding_rules.device_minor,
+                    padding_rules[&Block::SizeValue],
+                )),
+                None => strings.push(meta.size.render(
+                    colors,
+                    &flags,
+                    padding_rules[&Block::SizeValue],
+                )),
+            },
             Block::SizeValue => strings.push(meta.size.render_value(colors, flags)),
-            Block::Date => strings.push(meta.date.render(colors, &flags)),
+            Block::DiskUsage => {
+                let usage = Size::new(meta.allocated_bytes());
+                let s: &[ColoredString] = &[
+                    usage.render(colors, flags, padding_rules.disk_usage_value),
+                    ColoredString::from(if meta.is_sparse() { SPARSE_MARKER } else { "" }),
+                ];
+                strings.push(ColoredString::from(ANSIStrings(s).to_string()));
+            }
+            Block::Date => strings.push(render_date(meta.time(flags.time), colors, flags)),
+            Block::ATime => strings.push(render_date(meta.accessed.as_ref(), colors, flags)),
+            Block::CTime => strings.push(render_date(meta.changed.as_ref(), colors, flags)),
+            Block::BTime => strings.push(render_date(meta.created.as_ref(), colors, flags)),
             Block::Name => {
                 let s: String = if flags.no_symlink {
                     ANSIStrings(&[
-                        meta.name.render(colors, icons, &display_option),
+                        meta.name.render(
+                            colors,
+                            icons,
+                            &display_option,
+                            flags,
+                            padding_rules[&Block::Name],
+                        ),
                         meta.indicator.render(&flags),
                     ])
                     .to_string()
                 } else {
                     ANSIStrings(&[
-                        meta.name.render(colors, icons, &display_option),
+                        meta.name.render(
+                            colors,
+                            icons,
+                            &display_option,
+                            flags,
+                            padding_rules[&Block::Name],
+                        ),
                         meta.indicator.render(&flags),
-                        meta.symlink.render(colors),
+                        meta.symlink.render(colors, flags),
                     ])
                     .to_string()
                 };
@@ -294,56 +613,189 @@ fn get_output<'a>(
 }
 
 fn get_visible_width(input: &str) -> usize {
-    let mut nb_invisible_char = 0;
+    let mut visible = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
 
-    // If the input has color, do not compute the length contributed by the color to the actual length
-    for (idx, _) in input.match_indices("\u{1b}[") {
-        let (_, s) = input.split_at(idx);
+    // Do not compute the length contributed by the escape sequences: the
+    // colors (`ESC [ ... m`) and the hyperlinks (`ESC ] ... ESC \`).
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            visible.push(c);
+            continue;
+        }
 
-        let m_pos = s.find('m');
-        if let Some(len) = m_pos {
-            nb_invisible_char += len
+        match chars.next() {
+            Some('[') => {
+                for c in &mut chars {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' {
+                        break;
+                    }
+                    if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => (),
         }
     }
 
-    UnicodeWidthStr::width(input) - nb_invisible_char
+    UnicodeWidthStr::width(visible.as_str())
 }
 
-fn detect_size_lengths(metas: &[Meta], flags: &Flags) -> usize {
+/// Return the width of the size values and the width of the minor device
+/// numbers. The `major, minor` numbers of the devices are right-aligned with
+/// the size values.
+fn detect_size_lengths(metas: &[Meta], flags: &Flags) -> (usize, usize) {
     let mut max_value_length: usize = 0;
+    let mut max_minor_length: usize = 0;
+
+    for meta in metas {
+        if let Some((_, minor)) = meta.device.numbers() {
+            max_minor_length = max_minor_length.max(minor.to_string().len());
+        }
+    }
 
     for meta in metas {
-        let value_len = meta.size.value_string(flags).len();
+        let value_len = match meta.device.numbers() {
+            Some(_) => meta.device.value_string(max_minor_length).len(),
+            None => meta.size.value_string(flags).len(),
+        };
 
         if value_len > max_value_length {
             max_value_length = value_len;
         }
     }
 
-    max_value_length
+    (max_value_length, max_minor_length)
+}
+
+/// The widths aligning the blocks of the entries listed together.
+pub struct PaddingRules {
+    blocks: HashMap<Block, usize>,
+    /// The width of the minor numbers of the devices in the size block.
+    device_minor: usize,
+    /// The width of the values in the disk usage block, without the units.
+    disk_usage_value: usize,
+}
+
+impl Index<&Block> for PaddingRules {
+    type Output = usize;
+
+    fn index(&self, block: &Block) -> &usize {
+        &self.blocks[block]
+    }
 }
 
-fn get_padding_rules(metas: &[Meta], flags: &Flags) -> HashMap<Block, usize> {
+pub fn get_padding_rules(
+    metas: &[Meta],
+    flags: &Flags,
+    display_option: &DisplayOption,
+) -> PaddingRules {
     let mut padding_rules: HashMap<Block, usize> = HashMap::new();
+    let mut device_minor = 0;
+    let mut disk_usage_value = 0;
 
     if flags.blocks.contains(&Block::Size) {
-        let size_val = detect_size_lengths(&metas, &flags);
+        let (size_val, minor_val) = detect_size_lengths(&metas, &flags);
+
+        // The whole cell, with the unit after the value.
+        let size_len = metas
+            .iter()
+            .map(|meta| match meta.device.numbers() {
+                Some(_) => size_val,
+                None => size_val + 1 + meta.size.unit_string(flags).len(),
+            })
+            .max()
+            .unwrap_or(0);
 
         padding_rules.insert(Block::SizeValue, size_val);
+        device_minor = minor_val;
+        padding_rules.insert(Block::Size, size_len);
     }
 
-    padding_rules
+    if flags.blocks.contains(&Block::DiskUsage) {
+        let usage_val = metas
+            .iter()
+            .map(|meta| Size::new(meta.allocated_bytes()).value_string(flags).len())
+            .max()
+            .unwrap_or(0);
+
+        // The whole cell, with the unit and the sparse marker after the value.
+        let usage_len = metas
+            .iter()
+            .map(|meta| {
+                let unit = Size::new(meta.allocated_bytes()).unit_string(flags);
+                let marker = if meta.is_sparse() { SPARSE_MARKER } else { "" };
+                usage_val + 1 + unit.len() + marker.len()
+            })
+            .max()
+            .unwrap_or(0);
+
+        disk_usage_value = usage_val;
+        padding_rules.insert(Block::DiskUsage, usage_len);
+    }
+
+    if flags.blocks.contains(&Block::Links) {
+        let links_len = metas
+            .iter()
+            .map(|meta| meta.links.value_string().len())
+            .max()
+            .unwrap_or(0);
+
+        // Keep the counts right-aligned under their title.
+        let links_len = if flags.header {
+            links_len.max(Block::Links.title(flags.time).len())
+        } else {
+            links_len
+        };
+
+        padding_rules.insert(Block::Links, links_len);
+    }
+
+    if flags.blocks.contains(&Block::Name) {
+        // Shift the unquoted names when some names are quoted, to keep them
+        // aligned.
+        let quote_padding = if metas
+            .iter()
+            .any(|meta| meta.name.is_quoted(display_option, flags))
+        {
+            1
+        } else {
+            0
+        };
+
+        padding_rules.insert(Block::Name, quote_padding);
+    }
+
+    PaddingRules {
+        blocks: padding_rules,
+        device_minor,
+        disk_usage_value,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app;
     use crate::color;
     use crate::color::Colors;
+    use crate::config_file::Config;
+    use crate::flags::{QuotingStyle, WhenFlag};
     use crate::icon;
     use crate::icon::Icons;
     use crate::meta::{FileType, Name};
+    use std::fs::{self, File};
     use std::path::Path;
+    use tempfile::tempdir;
 
     #[test]
     fn test_display_get_visible_width_without_icons() {
@@ -368,6 +820,8 @@ mod tests {
                 &Colors::new(color::Theme::NoColor),
                 &Icons::new(icon::Theme::NoIcon),
                 &DisplayOption::FileName,
+                &Flags::default(),
+                0,
             );
 
             assert_eq!(get_visible_width(&output), *l);
@@ -400,6 +854,8 @@ mod tests {
                     &Colors::new(color::Theme::NoColor),
                     &Icons::new(icon::Theme::Fancy),
                     &DisplayOption::FileName,
+                    &Flags::default(),
+                    0,
                 )
                 .to_string();
 
@@ -432,6 +888,8 @@ mod tests {
                     &Colors::new(color::Theme::NoLscolors),
                     &Icons::new(icon::Theme::NoIcon),
                     &DisplayOption::FileName,
+                    &Flags::default(),
+                    0,
                 )
                 .to_string();
 
@@ -468,6 +926,8 @@ mod tests {
                     &Colors::new(color::Theme::NoColor),
                     &Icons::new(icon::Theme::NoIcon),
                     &DisplayOption::FileName,
+                    &Flags::default(),
+                    0,
                 )
                 .to_string();
 
@@ -478,4 +938,221 @@ mod tests {
             assert_eq!(get_visible_width(&output), *l);
         }
     }
+
+    #[test]
+    fn test_display_get_visible_width_with_hyperlinks() {
+        for (s, l) in &[
+            ("ASCII1234-_", 11),
+            ("File with space", 15),
+            ("日本語", 6),
+            ("🔬", 2),
+        ] {
+            let path = Path::new(s);
+            let name = Name::new(
+                path,
+                FileType::File {
+                    exec: false,
+                    uid: false,
+                },
+            );
+            let flags = Flags {
+                hyperlink: WhenFlag::Always,
+                ..Flags::default()
+            };
+            let output = name
+                .render(
+                    &Colors::new(color::Theme::NoLscolors),
+                    &Icons::new(icon::Theme::NoIcon),
+                    &DisplayOption::FileName,
+                    &flags,
+                    0,
+                )
+                .to_string();
+
+            // check if the link is present.
+            assert!(output.contains("\u{1b}]8;;file://"));
+
+            assert_eq!(get_visible_width(&output), *l);
+        }
+    }
+
+    #[test]
+    fn test_display_commas() {
+        let entries: Vec<(String, usize)> = ["one", "two", "three", "four"]
+            .iter()
+            .map(|entry| (entry.to_string(), entry.len()))
+            .collect();
+
+        assert_eq!("one, two, three, four\n", display_commas(&entries, 80));
+        assert_eq!("one, two,\nthree,\nfour\n", display_commas(&entries, 12));
+        assert_eq!("", display_commas(&[], 80));
+    }
+
+    #[test]
+    fn test_header_size_alignment() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let small_path = tmp_dir.path().join("small");
+        fs::write(&small_path, vec![0; 1000]).expect("failed to write file");
+        let large_path = tmp_dir.path().join("large");
+        fs::write(&large_path, vec![0; 2048]).expect("failed to write file");
+
+        let metas = vec![
+            Meta::from_path(&small_path).unwrap(),
+            Meta::from_path(&large_path).unwrap(),
+        ];
+        let flags = Flags {
+            blocks: vec![Block::Size],
+            layout: Layout::OneLine,
+            header: true,
+            ..Flags::default()
+        };
+
+        let output = inner_display_grid(
+            &DisplayOption::FileName,
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon),
+            1,
+            None,
+        );
+        let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+
+        // The title ends with the units.
+        assert_eq!(vec!["   Size", "1000 B", "   2 KB"], lines);
+    }
+
+    #[test]
+    fn test_header_disk_usage_alignment() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let empty_path = tmp_dir.path().join("empty");
+        File::create(&empty_path).expect("failed to create file");
+        let full_path = tmp_dir.path().join("full");
+        fs::write(&full_path, vec![1; 8192]).expect("failed to write file");
+
+        let metas = vec![
+            Meta::from_path(&empty_path).unwrap(),
+            Meta::from_path(&full_path).unwrap(),
+        ];
+        let flags = Flags {
+            blocks: vec![Block::DiskUsage],
+            ..Flags::default()
+        };
+        let colors = Colors::new(color::Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon);
+        let padding_rules = get_padding_rules(&metas, &flags, &DisplayOption::FileName);
+
+        // The padding covers the whole cells, with their units.
+        let width = metas
+            .iter()
+            .map(|meta| {
+                get_output(
+                    meta,
+                    &colors,
+                    &icons,
+                    &flags,
+                    &DisplayOption::FileName,
+                    &padding_rules,
+                )[0]
+                .len()
+            })
+            .max()
+            .unwrap();
+        assert_eq!(width, padding_rules[&Block::DiskUsage]);
+
+        let padding_rules = PaddingRules {
+            blocks: vec![(Block::DiskUsage, 12)].into_iter().collect(),
+            device_minor: 0,
+            disk_usage_value: 10,
+        };
+        let cells = header_cells(&flags, &colors, &padding_rules);
+        assert_eq!(12, cells[0].width);
+        assert_eq!("  Disk Usage", cells[0].contents);
+    }
+
+    #[test]
+    fn test_quote_padding_of_paths() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let dir_path = tmp_dir.path().join("my dir");
+        fs::create_dir(&dir_path).expect("failed to create dir");
+        let file_path = dir_path.join("file");
+        File::create(&file_path).expect("failed to create file");
+
+        let metas = vec![Meta::from_path(&file_path).unwrap()];
+        let flags = Flags {
+            blocks: vec![Block::Name],
+            quoting_style: Some(QuotingStyle::ShellEscape),
+            ..Flags::default()
+        };
+
+        // Only the path shown in full has quotes.
+        let rules = get_padding_rules(&metas, &flags, &DisplayOption::FileName);
+        assert_eq!(0, rules[&Block::Name]);
+        let rules = get_padding_rules(&metas, &flags, &DisplayOption::None);
+        assert_eq!(1, rules[&Block::Name]);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_immutable_permissions_style() {
+        use std::os::unix::io::AsRawFd;
+
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file");
+        let file = File::create(&file_path).expect("failed to create file");
+
+        // Setting the immutable flag needs privileges, like `chattr +i` does.
+        let set_flags = |flags: libc::c_int| unsafe {
+            libc::ioctl(file.as_raw_fd(), libc::FS_IOC_SETFLAGS as _, &flags) == 0
+        };
+        if !set_flags(0x10) {
+            return;
+        }
+
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "-l"])
+            .unwrap();
+        let flags = Flags::from_matches(&matches, &Config::default()).unwrap();
+        let meta = Meta::from_path(&file_path).unwrap().with_details(&flags);
+        set_flags(0);
+
+        let colors = Colors::new(color::Theme::NoLscolors);
+        let padding_rules = get_padding_rules(
+            std::slice::from_ref(&meta),
+            &flags,
+            &DisplayOption::FileName,
+        );
+        let output = get_output(
+            &meta,
+            &colors,
+            &Icons::new(icon::Theme::NoIcon),
+            &flags,
+            &DisplayOption::FileName,
+            &padding_rules,
+        )
+        .iter()
+        .map(ToString::to_string)
+        .collect::<String>();
+
+        // All the permissions are red, without any other style in between.
+        let permissions = meta
+            .permissions
+            .render(&Colors::new(color::Theme::NoColor))
+            .to_string();
+        let immutable = format!("\u{1b}[38;5;9m{}\u{1b}[", permissions);
+        assert!(output.contains(&immutable), "{:?}", output);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_detect_size_lengths_with_devices() {
+        let metas = vec![
+            Meta::from_path(Path::new("/dev/null")).unwrap(),
+            Meta::from_path(Path::new("/dev/tty")).unwrap(),
+        ];
+        let flags = Flags::default();
+
+        // "1, 3" and "5, 0"
+        assert_eq!((4, 1), detect_size_lengths(&metas, &flags));
+    }
 }
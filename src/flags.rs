@@ -1,41 +1,56 @@
+use crate::app::{parse_block_size, validate_date_argument};
+use crate::config_file::Config;
 use clap::{ArgMatches, Error, ErrorKind};
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
 use std::iter::Iterator;
 
 #[derive(Clone, Debug)]
 pub struct Flags {
     pub display: Display,
     pub layout: Layout,
+    pub format: Format,
     pub display_indicators: bool,
     pub recursive: bool,
     pub sort_by: SortFlag,
     pub sort_order: SortOrder,
     pub directory_order: DirOrderFlag,
     pub size: SizeFlag,
+    pub size_kind: SizeKind,
     pub date: DateFlag,
+    pub time: TimeFlag,
     pub color: WhenFlag,
+    pub color_theme: Option<String>,
     pub icon: WhenFlag,
     pub icon_theme: IconTheme,
+    pub icon_file: Option<String>,
     pub inode: bool,
     pub recursion_depth: usize,
     pub blocks: Vec<Block>,
+    pub permission: PermissionFlag,
+    pub numeric_ids: bool,
+    pub xattr: bool,
     pub no_symlink: bool,
     pub total_size: bool,
+    pub block_size: u64,
+    pub block_total: bool,
+    pub header: bool,
+    pub summary: bool,
+    pub print0: bool,
+    pub quoting_style: Option<QuotingStyle>,
+    pub hyperlink: WhenFlag,
     pub ignore_globs: GlobSet,
 }
 
 impl Flags {
-    pub fn from_matches(matches: &ArgMatches) -> Result<Self, Error> {
-        let classic_mode = matches.is_present("classic");
-        let color_inputs: Vec<&str> = matches.values_of("color").unwrap().collect();
-        let icon_inputs: Vec<&str> = matches.values_of("icon").unwrap().collect();
-        let icon_theme_inputs: Vec<&str> = matches.values_of("icon-theme").unwrap().collect();
-        let size_inputs: Vec<&str> = matches.values_of("size").unwrap().collect();
-        let date_inputs: Vec<&str> = matches.values_of("date").unwrap().collect();
-        let dir_order_inputs: Vec<&str> = matches.values_of("group-dirs").unwrap().collect();
-        let ignore_globs_inputs: Vec<&str> = matches.values_of("ignore-glob").unwrap().collect();
+    /// Build the flags from the command line, using the configuration file to
+    /// fill the values which are not given on the command line.
+    pub fn from_matches(matches: &ArgMatches, config: &Config) -> Result<Self, Error> {
+        let classic_mode = matches.is_present("classic") || config.classic == Some(true);
         // inode set layout to oneline and blocks to inode,name
-        let inode = matches.is_present("inode");
+        let inode = matches.is_present("inode") || config.inode == Some(true);
+        // context, like inode, sets the layout to oneline
+        let context = matches.is_present("context") || config.context == Some(true);
         let blocks_inputs: Vec<&str> = if let Some(blocks) = matches.values_of("blocks") {
             blocks.collect()
         } else {
@@ -48,6 +63,8 @@ impl Flags {
             Display::DisplayAlmostAll
         } else if matches.is_present("directory-only") {
             Display::DisplayDirectoryItself
+        } else if let Some(display) = config.display {
+            display
         } else {
             Display::DisplayOnlyVisible
         };
@@ -56,29 +73,64 @@ impl Flags {
             SortFlag::Time
         } else if matches.is_present("sizesort") {
             SortFlag::Size
+        } else if matches.is_present("linksort") {
+            SortFlag::Links
+        } else if let Some(sort_by) = config.sort {
+            sort_by
         } else {
             SortFlag::Name
         };
 
-        let sort_order = if matches.is_present("reverse") {
+        let sort_order = if matches.is_present("reverse") || config.reverse == Some(true) {
             SortOrder::Reverse
         } else {
             SortOrder::Default
         };
 
+        // Like in GNU ls, -n is a long view showing the numeric IDs.
+        let long = matches.is_present("long") || matches.is_present("numeric-uid-gid");
+
+        let config_blocks_len = config.blocks.as_ref().map_or(0, Vec::len);
         let layout = if matches.is_present("tree") {
             Layout::Tree
-        } else if matches.is_present("long")
+        } else if long
             || matches.is_present("oneline")
             || blocks_inputs.len() > 1
-            || inode
+            || matches.is_present("inode")
+            || matches.is_present("context")
         {
             Layout::OneLine
+        } else if matches.is_present("across") {
+            Layout::Across
+        } else if matches.is_present("commas") {
+            Layout::Commas
+        } else if let Some(layout) = config.layout {
+            layout
+        } else if config_blocks_len > 1 || inode || context {
+            Layout::OneLine
         } else {
             Layout::Grid
         };
 
-        let recursive = matches.is_present("recursive");
+        let block_size = match cli_value(matches, "block-size").or(config.block_size.as_deref()) {
+            Some(size) => match parse_block_size(size) {
+                Some(size) => size,
+                None => {
+                    return Err(Error::with_description(
+                        &format!("invalid block size: {}", size),
+                        ErrorKind::ValueValidation,
+                    ));
+                }
+            },
+            None => 1024,
+        };
+
+        let format = cli_value(matches, "format")
+            .map(Format::from)
+            .or(config.format)
+            .unwrap_or(Format::Text);
+
+        let recursive = matches.is_present("recursive") || config.recursive == Some(true);
         let recursion_input = matches.values_of("depth").and_then(Iterator::last);
         let recursion_depth = match recursion_input {
             Some(str) if recursive || layout == Layout::Tree => match str.parse::<usize>() {
@@ -96,13 +148,33 @@ impl Flags {
                     ErrorKind::MissingRequiredArgument,
                 ));
             }
-            None => usize::max_value(),
+            None => config.depth.unwrap_or_else(usize::max_value),
         };
 
         let mut blocks: Vec<Block> = if !blocks_inputs.is_empty() {
             blocks_inputs.into_iter().map(Block::from).collect()
-        } else if matches.is_present("long") {
+        } else if let Some(blocks) = &config.blocks {
+            blocks.clone()
+        } else if long {
+            let mut blocks = vec![
+                Block::Permission,
+                Block::User,
+                Block::Group,
+                Block::Size,
+                Block::Date,
+                Block::Name,
+            ];
+            // The link counts sit between the permissions and the owner, as
+            // in `ls -l`.
+            if matches.is_present("links") || config.links == Some(true) {
+                blocks.insert(1, Block::Links);
+            }
+            blocks
+        } else if format != Format::Text {
+            // The structured formats are meant for scripts, so give them
+            // every information unless told otherwise.
             vec![
+                Block::INode,
                 Block::Permission,
                 Block::User,
                 Block::Group,
@@ -119,6 +191,22 @@ impl Flags {
             blocks.insert(0, Block::INode);
         }
 
+        // Add the context before the name if with context flag, like `ls -Z`
+        if context && !blocks.contains(&Block::Context) {
+            match blocks.iter().position(|block| *block == Block::Name) {
+                Some(idx) => blocks.insert(idx, Block::Context),
+                None => blocks.push(Block::Context),
+            }
+        }
+
+        let ignore_globs_inputs: Vec<&str> = match cli_values(matches, "ignore-glob") {
+            Some(values) => values,
+            None => match &config.ignore_globs {
+                Some(globs) => globs.iter().map(String::as_str).collect(),
+                None => vec![],
+            },
+        };
+
         let mut ignore_globs_builder = GlobSetBuilder::new();
         for pattern in ignore_globs_inputs {
             let glob = match Glob::new(pattern) {
@@ -143,44 +231,136 @@ impl Flags {
             }
         };
 
+        // The streamed entries are printed before their content is read, hence
+        // the total size is ignored with ndjson unless both are given on the
+        // command line.
+        let total_size = matches.is_present("total-size") || config.total_size == Some(true);
+        if matches.is_present("total-size") && cli_value(matches, "format") == Some("ndjson") {
+            return Err(Error::with_description(
+                "The argument '--total-size' cannot be used with '--format ndjson'",
+                ErrorKind::ArgumentConflict,
+            ));
+        }
+        let total_size = total_size && format != Format::Ndjson;
+
+        let date = match (cli_value(matches, "date"), &config.date) {
+            (Some(date), _) => DateFlag::from(date),
+            (None, Some(date)) => match validate_date_argument(date.clone()) {
+                Ok(()) => DateFlag::from(date.as_str()),
+                Err(err) => {
+                    return Err(Error::with_description(
+                        &format!("invalid \"date\" value in the config file, {}", err),
+                        ErrorKind::ValueValidation,
+                    ));
+                }
+            },
+            (None, None) => DateFlag::Date,
+        };
+
         Ok(Self {
             display,
             layout,
-            display_indicators: matches.is_present("indicators"),
+            format,
+            display_indicators: matches.is_present("indicators") || config.indicators == Some(true),
             recursive,
             recursion_depth,
             sort_by,
             sort_order,
-            size: SizeFlag::from(size_inputs[size_inputs.len() - 1]),
+            size: cli_value(matches, "size")
+                .map(SizeFlag::from)
+                .or(config.size)
+                .unwrap_or(SizeFlag::Default),
+            size_kind: cli_value(matches, "size-kind")
+                .map(SizeKind::from)
+                .or(config.size_kind)
+                .unwrap_or(SizeKind::Apparent),
             ignore_globs,
             blocks,
-            // Take only the last value
-            date: if classic_mode {
-                DateFlag::Date
-            } else {
-                DateFlag::from(date_inputs[date_inputs.len() - 1])
-            },
+            date: if classic_mode { DateFlag::Date } else { date },
+            xattr: matches.is_present("xattr") || config.xattr == Some(true),
+            numeric_ids: matches.is_present("numeric-uid-gid")
+                || config.numeric_uid_gid == Some(true),
+            permission: cli_value(matches, "permission")
+                .map(PermissionFlag::from)
+                .or(config.permission)
+                .unwrap_or(PermissionFlag::Rwx),
+            time: cli_value(matches, "time")
+                .map(TimeFlag::from)
+                .or(config.time)
+                .unwrap_or(TimeFlag::Modified),
             color: if classic_mode {
                 WhenFlag::Never
             } else {
-                WhenFlag::from(color_inputs[color_inputs.len() - 1])
+                cli_value(matches, "color")
+                    .map(WhenFlag::from)
+                    .or(config.color)
+                    .unwrap_or(WhenFlag::Auto)
             },
+            color_theme: cli_value(matches, "theme")
+                .map(String::from)
+                .or_else(|| config.theme.clone()),
             icon: if classic_mode {
                 WhenFlag::Never
             } else {
-                WhenFlag::from(icon_inputs[icon_inputs.len() - 1])
+                cli_value(matches, "icon")
+                    .map(WhenFlag::from)
+                    .or(config.icon)
+                    .unwrap_or(WhenFlag::Auto)
             },
-            icon_theme: IconTheme::from(icon_theme_inputs[icon_theme_inputs.len() - 1]),
+            icon_theme: cli_value(matches, "icon-theme")
+                .map(IconTheme::from)
+                .or(config.icon_theme)
+                .unwrap_or(IconTheme::Fancy),
+            icon_file: cli_value(matches, "icon-file")
+                .map(String::from)
+                .or_else(|| config.icon_file.clone()),
             directory_order: if classic_mode {
                 DirOrderFlag::None
             } else {
-                DirOrderFlag::from(dir_order_inputs[dir_order_inputs.len() - 1])
+                cli_value(matches, "group-dirs")
+                    .map(DirOrderFlag::from)
+                    .or(config.group_dirs)
+                    .unwrap_or(DirOrderFlag::None)
             },
-            no_symlink: matches.is_present("no-symlink"),
-            total_size: matches.is_present("total-size"),
+            no_symlink: matches.is_present("no-symlink") || config.no_symlink == Some(true),
+            total_size,
+            block_size,
+            block_total: long && !(matches.is_present("no-total") || config.no_total == Some(true)),
+            header: matches.is_present("header") || config.header == Some(true),
+            summary: matches.is_present("summary") || config.summary == Some(true),
+            print0: matches.is_present("print0") || config.print0 == Some(true),
+            quoting_style: cli_value(matches, "quoting-style")
+                .map(QuotingStyle::from)
+                .or(config.quoting_style),
+            hyperlink: cli_value(matches, "hyperlink")
+                .map(WhenFlag::from)
+                .or(config.hyperlink)
+                .unwrap_or(WhenFlag::Auto),
             inode,
         })
     }
+
+    /// Return the quoting style of the names. Without an explicit choice,
+    /// the names are written literally.
+    pub fn quoting_style(&self) -> QuotingStyle {
+        self.quoting_style.unwrap_or(QuotingStyle::Literal)
+    }
+}
+
+/// Return the values explicitly given on the command line for the argument,
+/// ignoring its default value.
+fn cli_values<'a>(matches: &'a ArgMatches, name: &str) -> Option<Vec<&'a str>> {
+    if matches.occurrences_of(name) == 0 {
+        return None;
+    }
+
+    matches.values_of(name).map(Iterator::collect)
+}
+
+/// Return the last value explicitly given on the command line for the
+/// argument, ignoring its default value.
+fn cli_value<'a>(matches: &'a ArgMatches, name: &str) -> Option<&'a str> {
+    cli_values(matches, name).and_then(|values| values.last().copied())
 }
 
 impl Default for Flags {
@@ -188,6 +368,7 @@ impl Default for Flags {
         Self {
             display: Display::DisplayOnlyVisible,
             layout: Layout::Grid,
+            format: Format::Text,
             display_indicators: false,
             recursive: false,
             recursion_depth: usize::max_value(),
@@ -195,30 +376,96 @@ impl Default for Flags {
             sort_order: SortOrder::Default,
             directory_order: DirOrderFlag::None,
             size: SizeFlag::Default,
+            size_kind: SizeKind::Apparent,
             date: DateFlag::Date,
+            time: TimeFlag::Modified,
+            permission: PermissionFlag::Rwx,
+            numeric_ids: false,
+            xattr: false,
             color: WhenFlag::Auto,
+            color_theme: None,
             icon: WhenFlag::Auto,
             icon_theme: IconTheme::Fancy,
+            icon_file: None,
             blocks: vec![],
             no_symlink: false,
             total_size: false,
+            block_size: 1024,
+            block_total: false,
+            header: false,
+            summary: false,
+            print0: false,
+            quoting_style: None,
+            hyperlink: WhenFlag::Auto,
             ignore_globs: GlobSet::empty(),
             inode: false,
         }
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Block {
     Permission,
     User,
     Group,
     Size,
+    #[serde(skip)]
     SizeValue,
     Date,
     Name,
     INode,
+    Links,
+    #[serde(rename = "disk-usage")]
+    DiskUsage,
+    Octal,
+    Uid,
+    Gid,
+    Acl,
+    Context,
+    Attributes,
+    Capabilities,
+    #[serde(rename = "atime")]
+    ATime,
+    #[serde(rename = "ctime")]
+    CTime,
+    #[serde(rename = "btime")]
+    BTime,
+}
+
+impl Block {
+    /// Return the title of the block, as shown above its column. The title
+    /// of the date block depends on the timestamp it shows.
+    pub fn title(self, time: TimeFlag) -> &'static str {
+        match self {
+            Block::Date => match time {
+                TimeFlag::Modified => "Date Modified",
+                TimeFlag::Accessed => "Date Accessed",
+                TimeFlag::Changed => "Date Changed",
+                TimeFlag::Created => "Date Created",
+            },
+            Block::Octal => "Octal",
+            Block::Uid => "UID",
+            Block::Gid => "GID",
+            Block::Acl => "ACL",
+            Block::Context => "Security Context",
+            Block::Attributes => "Attributes",
+            Block::Capabilities => "Capabilities",
+            Block::ATime => "Date Accessed",
+            Block::CTime => "Date Changed",
+            Block::BTime => "Date Created",
+            Block::Permission => "Permissions",
+            Block::User => "User",
+            Block::Group => "Group",
+            Block::Size | Block::SizeValue => "Size",
+            Block::Name => "Name",
+            Block::INode => "INode",
+            Block::Links => "Links",
+            Block::DiskUsage => "Disk Usage",
+        }
+    }
 }
+
 impl<'a> From<&'a str> for Block {
     fn from(block: &'a str) -> Self {
         match block {
@@ -231,20 +478,37 @@ impl<'a> From<&'a str> for Block {
             "date" => Block::Date,
             "name" => Block::Name,
             "inode" => Block::INode,
+            "links" => Block::Links,
+            "disk-usage" => Block::DiskUsage,
+            "octal" => Block::Octal,
+            "uid" => Block::Uid,
+            "gid" => Block::Gid,
+            "acl" => Block::Acl,
+            "context" => Block::Context,
+            "attributes" => Block::Attributes,
+            "capabilities" => Block::Capabilities,
+            "atime" => Block::ATime,
+            "ctime" => Block::CTime,
+            "btime" => Block::BTime,
             _ => panic!("invalid \"time\" flag: {}", block),
         }
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
 pub enum Display {
+    #[serde(rename = "all")]
     DisplayAll,
+    #[serde(rename = "almost-all")]
     DisplayAlmostAll,
+    #[serde(rename = "directory-only")]
     DisplayDirectoryItself,
+    #[serde(rename = "visible-only")]
     DisplayOnlyVisible,
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SizeFlag {
     Default,
     Short,
@@ -262,6 +526,27 @@ impl<'a> From<&'a str> for SizeFlag {
     }
 }
 
+/// Which size of the files is used to sort them and to total the size of
+/// the directories.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeKind {
+    /// The length of the content.
+    Apparent,
+    /// The space allocated on the disk.
+    Allocated,
+}
+
+impl<'a> From<&'a str> for SizeKind {
+    fn from(kind: &'a str) -> Self {
+        match kind {
+            "apparent" => SizeKind::Apparent,
+            "allocated" => SizeKind::Allocated,
+            _ => panic!("invalid \"size-kind\" flag: {}", kind),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DateFlag {
     Date,
@@ -280,7 +565,8 @@ impl<'a> From<&'a str> for DateFlag {
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum WhenFlag {
     Always,
     Auto,
@@ -297,11 +583,13 @@ impl<'a> From<&'a str> for WhenFlag {
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortFlag {
     Name,
     Time,
     Size,
+    Links,
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
@@ -310,7 +598,55 @@ pub enum SortOrder {
     Reverse,
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+/// How to render the permission block.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionFlag {
+    /// The symbolic form, e.g. `rwxr-xr-x`.
+    Rwx,
+    /// The octal form, e.g. `0755`.
+    Octal,
+    /// The file attributes on Windows, the inode flags shown by `lsattr` on
+    /// Linux and the symbolic form elsewhere.
+    Attributes,
+}
+
+impl<'a> From<&'a str> for PermissionFlag {
+    fn from(permission: &'a str) -> Self {
+        match permission {
+            "rwx" => PermissionFlag::Rwx,
+            "octal" => PermissionFlag::Octal,
+            "attributes" => PermissionFlag::Attributes,
+            _ => panic!("invalid \"permission\" flag: {}", permission),
+        }
+    }
+}
+
+/// Which timestamp of the files is shown by the date block and used to sort
+/// them by time.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeFlag {
+    Modified,
+    Accessed,
+    Changed,
+    Created,
+}
+
+impl<'a> From<&'a str> for TimeFlag {
+    fn from(time: &'a str) -> Self {
+        match time {
+            "modified" => TimeFlag::Modified,
+            "accessed" => TimeFlag::Accessed,
+            "changed" => TimeFlag::Changed,
+            "created" => TimeFlag::Created,
+            _ => panic!("invalid \"time\" flag: {}", time),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DirOrderFlag {
     None,
     First,
@@ -328,7 +664,8 @@ impl<'a> From<&'a str> for DirOrderFlag {
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IconTheme {
     Unicode,
     Fancy,
@@ -344,17 +681,73 @@ impl<'a> From<&'a str> for IconTheme {
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuotingStyle {
+    Literal,
+    Shell,
+    ShellAlways,
+    ShellEscape,
+    C,
+    Escape,
+}
+
+impl<'a> From<&'a str> for QuotingStyle {
+    fn from(style: &'a str) -> Self {
+        match style {
+            "literal" => QuotingStyle::Literal,
+            "shell" => QuotingStyle::Shell,
+            "shell-always" => QuotingStyle::ShellAlways,
+            "shell-escape" => QuotingStyle::ShellEscape,
+            "c" => QuotingStyle::C,
+            "escape" => QuotingStyle::Escape,
+            _ => panic!("invalid \"quoting-style\" flag: {}", style),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Layout {
     Grid,
     Tree,
     OneLine,
+    Across,
+    Commas,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Html,
+    Markdown,
+}
+
+impl<'a> From<&'a str> for Format {
+    fn from(format: &'a str) -> Self {
+        match format {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "ndjson" => Format::Ndjson,
+            "csv" => Format::Csv,
+            "tsv" => Format::Tsv,
+            "html" => Format::Html,
+            "markdown" => Format::Markdown,
+            _ => panic!("invalid \"format\" flag: {}", format),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Flags;
+    use super::{Block, Flags, Format, Layout, WhenFlag};
     use crate::app;
+    use crate::config_file::Config;
     use clap::ErrorKind;
 
     #[test]
@@ -362,7 +755,7 @@ mod test {
         let matches = app::build()
             .get_matches_from_safe(vec!["lsd", "--tree", "--depth", "xx"])
             .unwrap();
-        let res = Flags::from_matches(&matches);
+        let res = Flags::from_matches(&matches, &Config::default());
 
         assert!(res.is_err());
         assert_eq!(res.unwrap_err().kind, ErrorKind::ValueValidation);
@@ -373,7 +766,7 @@ mod test {
         let matches = app::build()
             .get_matches_from_safe(vec!["lsd", "--depth", "10"])
             .unwrap();
-        let res = Flags::from_matches(&matches);
+        let res = Flags::from_matches(&matches, &Config::default());
 
         assert!(res.is_err());
         assert_eq!(res.unwrap_err().kind, ErrorKind::MissingRequiredArgument);
@@ -384,7 +777,7 @@ mod test {
         let matches = app::build()
             .get_matches_from_safe(vec!["lsd", "--tree", "--depth", "1", "--depth", "2"])
             .unwrap();
-        let res = Flags::from_matches(&matches);
+        let res = Flags::from_matches(&matches, &Config::default());
 
         assert!(res.is_ok());
         assert_eq!(res.unwrap().recursion_depth, 2);
@@ -395,9 +788,140 @@ mod test {
         let matches = app::build()
             .get_matches_from_safe(vec!["lsd", "--tree"])
             .unwrap();
-        let res = Flags::from_matches(&matches);
+        let res = Flags::from_matches(&matches, &Config::default());
 
         assert!(res.is_ok());
         assert_eq!(res.unwrap().recursion_depth, usize::max_value());
     }
+
+    #[test]
+    fn test_config_is_used_without_cli_value() {
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        let config = Config {
+            color: Some(WhenFlag::Never),
+            blocks: Some(vec![Block::Size, Block::Name]),
+            depth: Some(3),
+            ..Config::default()
+        };
+        let flags = Flags::from_matches(&matches, &config).unwrap();
+
+        assert_eq!(WhenFlag::Never, flags.color);
+        assert_eq!(vec![Block::Size, Block::Name], flags.blocks);
+        assert_eq!(Layout::OneLine, flags.layout);
+        assert_eq!(3, flags.recursion_depth);
+    }
+
+    #[test]
+    fn test_cli_overrides_config() {
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--color", "always", "--tree"])
+            .unwrap();
+        let config = Config {
+            color: Some(WhenFlag::Never),
+            layout: Some(Layout::OneLine),
+            ..Config::default()
+        };
+        let flags = Flags::from_matches(&matches, &config).unwrap();
+
+        assert_eq!(WhenFlag::Always, flags.color);
+        assert_eq!(Layout::Tree, flags.layout);
+    }
+
+    #[test]
+    fn test_total_size_with_ndjson() {
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--format", "ndjson", "--total-size"])
+            .unwrap();
+        let res = Flags::from_matches(&matches, &Config::default());
+
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_total_size_in_config_with_ndjson() {
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--format", "ndjson"])
+            .unwrap();
+        let config = Config {
+            total_size: Some(true),
+            ..Config::default()
+        };
+        let flags = Flags::from_matches(&matches, &config).unwrap();
+
+        assert_eq!(Format::Ndjson, flags.format);
+        assert!(!flags.total_size);
+    }
+
+    #[test]
+    fn test_invalid_date_in_config() {
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        let config = Config {
+            date: Some("yesterday".to_string()),
+            ..Config::default()
+        };
+        let res = Flags::from_matches(&matches, &config);
+
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_block_size() {
+        for (arg, size) in &[
+            ("512", 512),
+            ("K", 1024),
+            ("4K", 4096),
+            ("1KiB", 1024),
+            ("1MB", 1_000_000),
+            ("m", 1_048_576),
+        ] {
+            let matches = app::build()
+                .get_matches_from_safe(vec!["lsd", "--block-size", arg])
+                .unwrap();
+            let flags = Flags::from_matches(&matches, &Config::default()).unwrap();
+
+            assert_eq!(*size, flags.block_size);
+        }
+    }
+
+    #[test]
+    fn test_links_in_long_view() {
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--long", "--links"])
+            .unwrap();
+        let flags = Flags::from_matches(&matches, &Config::default()).unwrap();
+
+        assert_eq!(Block::Permission, flags.blocks[0]);
+        assert_eq!(Block::Links, flags.blocks[1]);
+        assert_eq!(Block::User, flags.blocks[2]);
+    }
+
+    #[test]
+    fn test_context_before_name() {
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--long", "--context"])
+            .unwrap();
+        let flags = Flags::from_matches(&matches, &Config::default()).unwrap();
+        assert_eq!(
+            Some(&Block::Context),
+            flags.blocks.get(flags.blocks.len() - 2)
+        );
+
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--tree", "-Z"])
+            .unwrap();
+        let flags = Flags::from_matches(&matches, &Config::default()).unwrap();
+        assert_eq!(Layout::Tree, flags.layout);
+        assert_eq!(vec![Block::Context, Block::Name], flags.blocks);
+    }
+
+    #[test]
+    fn test_invalid_block_size() {
+        for arg in &["0", "4X", "KBB", "-1"] {
+            let res = app::build().get_matches_from_safe(vec!["lsd", "--block-size", arg]);
+
+            assert!(res.is_err(), "{} should be rejected", arg);
+        }
+    }
 }
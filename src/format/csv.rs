@@ -0,0 +1,240 @@
+use crate::color::{self, Colors};
+use crate::flags::{Block, Display, Flags};
+use crate::meta::{Date, FileType, Meta};
+
+/// Render the metas as a table of delimiter-separated values: a header row
+/// with the selected blocks, then one row per entry.
+///
+/// The values are raw (sizes in bytes, dates in ISO 8601) and quoted following
+/// RFC 4180, so any file name can be read back.
+pub fn render(metas: &[Meta], flags: &Flags, delimiter: char) -> String {
+    let mut output = String::new();
+
+    let header: Vec<&str> = flags
+        .blocks
+        .iter()
+        .flat_map(|block| columns(*block, flags))
+        .collect();
+    push_row(&mut output, &header, delimiter);
+
+    // The directories given by the user are replaced by their content, as in
+    // the default output.
+    let skip_dirs = flags.display != Display::DisplayDirectoryItself;
+    let no_colors = Colors::new(color::Theme::NoColor);
+    for meta in metas {
+        match (skip_dirs, meta.file_type, &meta.content) {
+            (true, FileType::Directory { .. }, Some(content)) => {
+                push_metas(&mut output, content, flags, &no_colors, delimiter)
+            }
+            _ => push_metas(
+                &mut output,
+                std::slice::from_ref(meta),
+                flags,
+                &no_colors,
+                delimiter,
+            ),
+        }
+    }
+
+    output
+}
+
+/// Return the header of the columns filled by a block.
+fn columns(block: Block, flags: &Flags) -> Vec<&'static str> {
+    match block {
+        Block::Permission if has_octal_block(flags) => vec!["permission"],
+        Block::Permission => vec!["permission", "octal"],
+        Block::User => vec!["user"],
+        Block::Group => vec!["group"],
+        Block::Size | Block::SizeValue => vec!["size"],
+        Block::Date => vec!["date"],
+        Block::ATime => vec!["accessed"],
+        Block::CTime => vec!["changed"],
+        Block::BTime => vec!["created"],
+        Block::Name => vec!["name"],
+        Block::INode => vec!["inode"],
+        Block::Links => vec!["links"],
+        Block::Octal => vec!["octal"],
+        Block::Uid => vec!["uid"],
+        Block::Gid => vec!["gid"],
+        Block::Acl => vec!["acl"],
+        Block::Context => vec!["context"],
+        Block::Attributes => vec!["attributes"],
+        Block::Capabilities => vec!["capabilities"],
+        Block::DiskUsage => vec!["disk_usage"],
+    }
+}
+
+/// Whether the octal block is selected, in which case the permission block
+/// doesn't add its own octal column.
+fn has_octal_block(flags: &Flags) -> bool {
+    flags.blocks.contains(&Block::Octal)
+}
+
+fn iso_string(date: Option<&Date>) -> String {
+    date.map_or_else(String::new, Date::iso_string)
+}
+
+fn push_metas(
+    output: &mut String,
+    metas: &[Meta],
+    flags: &Flags,
+    no_colors: &Colors,
+    delimiter: char,
+) {
+    for meta in metas {
+        let mut row: Vec<String> = Vec::new();
+        for block in &flags.blocks {
+            match block {
+                Block::Permission => {
+                    row.push(format!(
+                        "{}{}",
+                        meta.file_type.render(no_colors),
+                        meta.permissions.render(no_colors)
+                    ));
+                    if !has_octal_block(flags) {
+                        row.push(meta.permissions.octal_string());
+                    }
+                }
+                Block::User => row.push(meta.owner.user().to_string()),
+                Block::Group => row.push(meta.owner.group().to_string()),
+                Block::Size | Block::SizeValue => row.push(meta.size.get_bytes().to_string()),
+                Block::Date => row.push(iso_string(meta.time(flags.time))),
+                Block::ATime => row.push(iso_string(meta.accessed.as_ref())),
+                Block::CTime => row.push(iso_string(meta.changed.as_ref())),
+                Block::BTime => row.push(iso_string(meta.created.as_ref())),
+                Block::Name => row.push(meta.path.to_string_lossy().to_string()),
+                Block::INode => row.push(
+                    meta.inode
+                        .index()
+                        .map_or_else(String::new, |index| index.to_string()),
+                ),
+                Block::DiskUsage => row.push(
+                    meta.blocks
+                        .count()
+                        .map_or_else(String::new, |_| meta.blocks.bytes().to_string()),
+                ),
+                Block::Octal => row.push(meta.permissions.octal_string()),
+                Block::Acl => row.push(meta.acl.acl_string(flags)),
+                Block::Context => row.push(meta.context.context_string()),
+                Block::Attributes => row.push(meta.inode_flags.flags_string()),
+                Block::Capabilities => row.push(meta.capabilities.capabilities_string()),
+                Block::Uid => row.push(
+                    meta.owner
+                        .uid()
+                        .map_or_else(String::new, |uid| uid.to_string()),
+                ),
+                Block::Gid => row.push(
+                    meta.owner
+                        .gid()
+                        .map_or_else(String::new, |gid| gid.to_string()),
+                ),
+                Block::Links => row.push(
+                    meta.links
+                        .nlink()
+                        .map_or_else(String::new, |nlink| nlink.to_string()),
+                ),
+            }
+        }
+
+        let row: Vec<&str> = row.iter().map(String::as_str).collect();
+        push_row(output, &row, delimiter);
+
+        if let Some(content) = &meta.content {
+            push_metas(output, content, flags, no_colors, delimiter);
+        }
+    }
+}
+
+fn push_row(output: &mut String, fields: &[&str], delimiter: char) {
+    for (idx, field) in fields.iter().enumerate() {
+        if idx > 0 {
+            output.push(delimiter);
+        }
+        output.push_str(&quote(field, delimiter));
+    }
+    output.push_str("\r\n");
+}
+
+/// Quote a field if it contains the delimiter, a double quote or a line
+/// break. The double quotes inside are doubled.
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains(&[delimiter, '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{quote, render};
+    use crate::color::{Colors, Theme};
+    use crate::flags::{Block, Flags};
+    use crate::meta::Meta;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!("plain", quote("plain", ','));
+        assert_eq!("\"a,b\"", quote("a,b", ','));
+        assert_eq!("a,b", quote("a,b", '\t'));
+        assert_eq!("\"a\tb\"", quote("a\tb", '\t'));
+        assert_eq!("\"say \"\"hi\"\"\"", quote("say \"hi\"", ','));
+        assert_eq!("\"two\nlines\"", quote("two\nlines", ','));
+    }
+
+    #[test]
+    fn test_render_permission_with_octal_block() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file.txt");
+        File::create(&file_path).expect("failed to create file");
+        let meta = Meta::from_path(&file_path).unwrap();
+
+        let flags = Flags {
+            blocks: vec![Block::Permission, Block::Name],
+            ..Flags::default()
+        };
+        let output = render(std::slice::from_ref(&meta), &flags, ',');
+        assert_eq!(Some("permission,octal,name"), output.lines().next());
+
+        // The octal column is not repeated.
+        let flags = Flags {
+            blocks: vec![Block::Permission, Block::Octal, Block::Name],
+            ..Flags::default()
+        };
+        let output = render(std::slice::from_ref(&meta), &flags, ',');
+        let row = format!(
+            "{}{},{},{}",
+            meta.file_type.render(&Colors::new(Theme::NoColor)),
+            meta.permissions.render(&Colors::new(Theme::NoColor)),
+            meta.permissions.octal_string(),
+            file_path.display()
+        );
+        assert_eq!(
+            vec!["permission,octal,name", row.as_str()],
+            output.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_render_directory_content() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        File::create(tmp_dir.path().join("a,b.txt")).expect("failed to create file");
+        let mut meta = Meta::from_path(tmp_dir.path()).unwrap();
+        let flags = Flags {
+            blocks: vec![Block::Size, Block::Name],
+            ..Flags::default()
+        };
+        meta.content = meta.recurse_into(1, &flags).unwrap();
+
+        assert_eq!(
+            format!(
+                "size,name\r\n0,\"{}\"\r\n",
+                tmp_dir.path().join("a,b.txt").display()
+            ),
+            render(&[meta], &flags, ',')
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use crate::color::Colors;
+use crate::display::{get_output, get_padding_rules, should_display_folder_path};
+use crate::flags::{Display, Flags, Layout};
+use crate::icon::Icons;
+use crate::meta::name::DisplayOption;
+use crate::meta::{FileType, Meta};
+
+const BLOCK_STYLE: &str = "font-family: monospace; white-space: pre";
+
+/// Render the metas as HTML: a table per listed directory, or nested lists
+/// with `--tree`. The colors chosen by `Colors` are kept as inline CSS.
+pub fn render(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
+    if flags.layout == Layout::Tree {
+        render_tree(metas, flags, colors, icons)
+    } else {
+        render_tables(&DisplayOption::None, metas, flags, colors, icons, 0)
+    }
+}
+
+fn render_tables(
+    display_option: &DisplayOption,
+    metas: &[Meta],
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+    depth: usize,
+) -> String {
+    let mut output = String::new();
+
+    let padding_rules = get_padding_rules(metas, flags, display_option);
+
+    // As in the default output, the directories given by the user are
+    // displayed through their content.
+    let skip_dirs = (depth == 0) && (flags.display != Display::DisplayDirectoryItself);
+    let rows: Vec<&Meta> = metas
+        .iter()
+        .filter(|meta| !(skip_dirs && matches!(meta.file_type, FileType::Directory { .. })))
+        .collect();
+
+    if !rows.is_empty() {
+        output += &format!("<table style=\"{}\">\n<tr>", BLOCK_STYLE);
+        for block in &flags.blocks {
+            output += &format!("<th>{}</th>", block.title(flags.time));
+        }
+        output += "</tr>\n";
+
+        for meta in rows {
+            output += "<tr>";
+            for block in get_output(meta, colors, icons, flags, display_option, &padding_rules) {
+                output += &format!("<td>{}</td>", ansi_to_html(&block.to_string()));
+            }
+            output += "</tr>\n";
+        }
+
+        output += "</table>\n";
+    }
+
+    let should_display_folder_path = should_display_folder_path(depth, metas);
+
+    for meta in metas {
+        if let Some(content) = &meta.content {
+            if should_display_folder_path {
+                output += &format!(
+                    "<p><strong>{}:</strong></p>\n",
+                    escape(&meta.path.to_string_lossy())
+                );
+            }
+
+            let display_option = DisplayOption::Relative {
+                base_path: &meta.path,
+            };
+
+            output += &render_tables(&display_option, content, flags, colors, icons, depth + 1);
+        }
+    }
+
+    output
+}
+
+fn render_tree(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
+    let mut output = format!(
+        "<ul style=\"{}; list-style: none; padding-left: 2em\">\n",
+        BLOCK_STYLE
+    );
+
+    let padding_rules = get_padding_rules(metas, flags, &DisplayOption::FileName);
+
+    for meta in metas {
+        let blocks: Vec<String> = get_output(
+            meta,
+            colors,
+            icons,
+            flags,
+            &DisplayOption::FileName,
+            &padding_rules,
+        )
+        .iter()
+        .map(|block| ansi_to_html(&block.to_string()))
+        .collect();
+
+        output += "<li>";
+        output += &blocks.join(" ");
+
+        if let Some(content) = &meta.content {
+            output += "\n";
+            output += &render_tree(content, flags, colors, icons);
+        }
+
+        output += "</li>\n";
+    }
+
+    output += "</ul>\n";
+
+    output
+}
+
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// The style set by the ANSI escape sequences, as it will be written in CSS.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct CssStyle {
+    foreground: Option<String>,
+    background: Option<String>,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl CssStyle {
+    /// Update the style with the parameters of an SGR escape sequence.
+    fn apply(&mut self, params: &[u16]) {
+        let mut idx = 0;
+
+        while idx < params.len() {
+            match params[idx] {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => self.bold = false,
+                23 => self.italic = false,
+                24 => self.underline = false,
+                code @ 30..=37 => self.foreground = Some(colour_hex(code - 30)),
+                code @ 90..=97 => self.foreground = Some(colour_hex(code - 90 + 8)),
+                code @ 40..=47 => self.background = Some(colour_hex(code - 40)),
+                code @ 100..=107 => self.background = Some(colour_hex(code - 100 + 8)),
+                39 => self.foreground = None,
+                49 => self.background = None,
+                code @ 38 | code @ 48 => {
+                    let colour = match params.get(idx + 1) {
+                        Some(5) => {
+                            let colour = params.get(idx + 2).map(|index| colour_hex(*index));
+                            idx += 2;
+                            colour
+                        }
+                        Some(2) => {
+                            let component = |offset| params.get(idx + offset).copied();
+                            let colour = match (component(2), component(3), component(4)) {
+                                (Some(r), Some(g), Some(b)) => {
+                                    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+                                }
+                                _ => None,
+                            };
+                            idx += 4;
+                            colour
+                        }
+                        _ => None,
+                    };
+
+                    if code == 38 {
+                        self.foreground = colour;
+                    } else {
+                        self.background = colour;
+                    }
+                }
+                _ => (),
+            }
+
+            idx += 1;
+        }
+    }
+
+    fn to_css(&self) -> String {
+        let mut css = Vec::new();
+
+        if let Some(colour) = &self.foreground {
+            css.push(format!("color: {}", colour));
+        }
+        if let Some(colour) = &self.background {
+            css.push(format!("background-color: {}", colour));
+        }
+        if self.bold {
+            css.push("font-weight: bold".to_string());
+        }
+        if self.italic {
+            css.push("font-style: italic".to_string());
+        }
+        if self.underline {
+            css.push("text-decoration: underline".to_string());
+        }
+
+        css.join("; ")
+    }
+}
+
+/// Convert a string colored with ANSI escape sequences into escaped HTML,
+/// with a styled `<span>` for each colored part.
+fn ansi_to_html(input: &str) -> String {
+    let mut output = String::new();
+    let mut style = CssStyle::default();
+    let mut rest = input;
+
+    loop {
+        let (text, sequence) = match rest.find("\u{1b}[") {
+            Some(start) => match rest[start + 2..].find('m') {
+                Some(len) => (
+                    &rest[..start],
+                    Some((&rest[start + 2..start + 2 + len], start + 3 + len)),
+                ),
+                None => (rest, None),
+            },
+            None => (rest, None),
+        };
+
+        if !text.is_empty() {
+            let css = style.to_css();
+            if css.is_empty() {
+                output += &escape(text);
+            } else {
+                output += &format!("<span style=\"{}\">{}</span>", css, escape(text));
+            }
+        }
+
+        match sequence {
+            Some((params, next)) => {
+                let params: Vec<u16> = params
+                    .split(';')
+                    .map(|param| param.parse().unwrap_or(0))
+                    .collect();
+                style.apply(&params);
+                rest = &rest[next..];
+            }
+            None => break,
+        }
+    }
+
+    output
+}
+
+/// Return the hexadecimal value of a color of the 256-color palette, using
+/// the xterm defaults.
+fn colour_hex(index: u16) -> String {
+    const BASE: [&str; 16] = [
+        "#000000", "#800000", "#008000", "#808000", "#000080", "#800080", "#008080", "#c0c0c0",
+        "#808080", "#ff0000", "#00ff00", "#ffff00", "#0000ff", "#ff00ff", "#00ffff", "#ffffff",
+    ];
+    const CUBE: [u16; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => BASE[index as usize].to_string(),
+        16..=231 => {
+            let index = index - 16;
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                CUBE[(index / 36) as usize],
+                CUBE[(index / 6 % 6) as usize],
+                CUBE[(index % 6) as usize]
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index.min(255) - 232);
+            format!("#{:02x}{:02x}{:02x}", level, level, level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ansi_to_html, colour_hex};
+    use ansi_term::{Colour, Style};
+
+    #[test]
+    fn test_ansi_to_html() {
+        assert_eq!("a &lt;b&gt; &amp; c", ansi_to_html("a <b> & c"));
+        assert_eq!(
+            "<span style=\"color: #00ff00\">file</span>",
+            ansi_to_html(&Colour::Fixed(10).paint("file").to_string())
+        );
+        assert_eq!(
+            "<span style=\"color: #ff8000; font-weight: bold\">dir</span> x",
+            ansi_to_html(&format!(
+                "{} x",
+                Style::default()
+                    .fg(Colour::RGB(255, 128, 0))
+                    .bold()
+                    .paint("dir")
+            ))
+        );
+        assert_eq!(
+            "<span style=\"color: #800000; background-color: #af0000\">suid</span>",
+            ansi_to_html(&Colour::Red.on(Colour::Fixed(124)).paint("suid").to_string())
+        );
+    }
+
+    #[test]
+    fn test_colour_hex() {
+        assert_eq!("#000000", colour_hex(0));
+        assert_eq!("#ffffff", colour_hex(15));
+        assert_eq!("#ff8700", colour_hex(208));
+        assert_eq!("#eeeeee", colour_hex(255));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::color::{self, Colors};
+use crate::flags::{Block, Flags};
+use crate::format::file_type_name;
+use crate::meta::{Date, Meta};
+use serde_json::{Map, Value};
+
+/// Render the metas as a JSON array. The directories listed recursively have
+/// their entries in a nested `content` array.
+pub fn render(metas: &[Meta], flags: &Flags) -> String {
+    let values: Vec<Value> = metas.iter().map(|meta| to_value(meta, flags)).collect();
+
+    let mut output = serde_json::to_string_pretty(&values).expect("failed to serialize the metas");
+    output.push('\n');
+
+    output
+}
+
+fn to_value(meta: &Meta, flags: &Flags) -> Value {
+    let mut object = to_object(meta, flags);
+
+    if let Some(content) = &meta.content {
+        let content = content.iter().map(|meta| to_value(meta, flags)).collect();
+        object.insert("content".to_string(), Value::Array(content));
+    }
+
+    Value::Object(object)
+}
+
+/// Return the fields of a single meta, without its content. The name, the
+/// path and the file type are always present, the other fields follow the
+/// selected blocks.
+pub fn to_object(meta: &Meta, flags: &Flags) -> Map<String, Value> {
+    let mut object = Map::new();
+
+    object.insert("name".to_string(), Value::from(meta.name.name.as_str()));
+    object.insert(
+        "path".to_string(),
+        Value::from(meta.path.to_string_lossy().as_ref()),
+    );
+    object.insert(
+        "file_type".to_string(),
+        Value::from(file_type_name(meta.file_type)),
+    );
+
+    let no_colors = Colors::new(color::Theme::NoColor);
+    for block in &flags.blocks {
+        match block {
+            Block::INode => {
+                object.insert("inode".to_string(), Value::from(meta.inode.index()));
+            }
+            Block::DiskUsage => {
+                object.insert(
+                    "disk_usage".to_string(),
+                    Value::from(meta.blocks.count().map(|_| meta.blocks.bytes())),
+                );
+            }
+            Block::Context => {
+                object.insert(
+                    "context".to_string(),
+                    Value::from(meta.context.context_string()),
+                );
+            }
+            Block::Attributes => {
+                object.insert(
+                    "attributes".to_string(),
+                    Value::from(meta.inode_flags.flags_string()),
+                );
+            }
+            Block::Capabilities => {
+                object.insert(
+                    "capabilities".to_string(),
+                    Value::from(meta.capabilities.capabilities_string()),
+                );
+            }
+            Block::Acl => {
+                object.insert("acl".to_string(), Value::from(meta.acl.acl_string(flags)));
+            }
+            Block::Uid => {
+                object.insert("uid".to_string(), Value::from(meta.owner.uid()));
+            }
+            Block::Gid => {
+                object.insert("gid".to_string(), Value::from(meta.owner.gid()));
+            }
+            Block::Octal => {
+                object.insert(
+                    "octal".to_string(),
+                    Value::from(meta.permissions.octal_string()),
+                );
+            }
+            Block::Links => {
+                object.insert("links".to_string(), Value::from(meta.links.nlink()));
+            }
+            Block::Permission => {
+                object.insert(
+                    "permissions".to_string(),
+                    Value::from(meta.permissions.render(&no_colors).to_string()),
+                );
+            }
+            Block::User => {
+                object.insert("user".to_string(), Value::from(meta.owner.user()));
+            }
+            Block::Group => {
+                object.insert("group".to_string(), Value::from(meta.owner.group()));
+            }
+            Block::Size | Block::SizeValue => {
+                object.insert("size".to_string(), Value::from(meta.size.get_bytes()));
+            }
+            Block::Date => {
+                object.insert("date".to_string(), iso_value(meta.time(flags.time)));
+            }
+            Block::ATime => {
+                object.insert("accessed".to_string(), iso_value(meta.accessed.as_ref()));
+            }
+            Block::CTime => {
+                object.insert("changed".to_string(), iso_value(meta.changed.as_ref()));
+            }
+            Block::BTime => {
+                object.insert("created".to_string(), iso_value(meta.created.as_ref()));
+            }
+            Block::Name => {
+                if let (false, Some(target)) = (flags.no_symlink, meta.symlink.symlink_string()) {
+                    let mut symlink = Map::new();
+                    symlink.insert("target".to_string(), Value::from(target));
+                    symlink.insert("valid".to_string(), Value::from(meta.symlink.is_valid()));
+                    object.insert("symlink".to_string(), Value::Object(symlink));
+                }
+            }
+        }
+    }
+
+    object
+}
+
+fn iso_value(date: Option<&Date>) -> Value {
+    Value::from(date.map(Date::iso_string))
+}
+
+#[cfg(test)]
+mod test {
+    use super::render;
+    use crate::flags::{Block, Flags};
+    use crate::meta::Meta;
+    use serde_json::Value;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_selected_blocks() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file.txt");
+        File::create(&file_path).expect("failed to create file");
+        let meta = Meta::from_path(&file_path).unwrap();
+
+        let flags = Flags {
+            blocks: vec![Block::Size, Block::Name],
+            ..Flags::default()
+        };
+
+        let output: Value = serde_json::from_str(&render(&[meta], &flags)).unwrap();
+        let entry = &output[0];
+
+        assert_eq!("file.txt", entry["name"]);
+        assert_eq!("file", entry["file_type"]);
+        assert_eq!(0, entry["size"]);
+        assert!(entry.get("user").is_none());
+        assert!(entry.get("content").is_none());
+    }
+
+    #[test]
+    fn test_render_nested_content() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        File::create(tmp_dir.path().join("file.txt")).expect("failed to create file");
+        let mut meta = Meta::from_path(tmp_dir.path()).unwrap();
+        let flags = Flags::default();
+        meta.content = meta.recurse_into(1, &flags).unwrap();
+
+        let output: Value = serde_json::from_str(&render(&[meta], &flags)).unwrap();
+
+        assert_eq!("directory", output[0]["file_type"]);
+        assert_eq!("file.txt", output[0]["content"][0]["name"]);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::color::{self, Colors};
+use crate::display::{get_output, get_padding_rules, should_display_folder_path};
+use crate::flags::{Display, Flags, Layout};
+use crate::icon::Icons;
+use crate::meta::name::DisplayOption;
+use crate::meta::{FileType, Meta};
+
+/// Render the metas as Markdown: a pipe table of the selected blocks per
+/// listed directory, or nested lists with `--tree`.
+pub fn render(metas: &[Meta], flags: &Flags, icons: &Icons) -> String {
+    let no_colors = Colors::new(color::Theme::NoColor);
+
+    if flags.layout == Layout::Tree {
+        render_tree(metas, flags, &no_colors, icons, 0)
+    } else {
+        render_tables(&DisplayOption::None, metas, flags, &no_colors, icons, 0)
+    }
+}
+
+fn render_tables(
+    display_option: &DisplayOption,
+    metas: &[Meta],
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+    depth: usize,
+) -> String {
+    let mut output = String::new();
+
+    let padding_rules = get_padding_rules(metas, flags, display_option);
+
+    // As in the default output, the directories given by the user are
+    // displayed through their content.
+    let skip_dirs = (depth == 0) && (flags.display != Display::DisplayDirectoryItself);
+    let rows: Vec<&Meta> = metas
+        .iter()
+        .filter(|meta| !(skip_dirs && matches!(meta.file_type, FileType::Directory { .. })))
+        .collect();
+
+    if !rows.is_empty() {
+        let titles: Vec<&str> = flags
+            .blocks
+            .iter()
+            .map(|block| block.title(flags.time))
+            .collect();
+        output += &format!("| {} |\n", titles.join(" | "));
+        output += &format!("|{}\n", " --- |".repeat(titles.len()));
+
+        for meta in rows {
+            let cells: Vec<String> =
+                get_output(meta, colors, icons, flags, display_option, &padding_rules)
+                    .iter()
+                    .map(|block| escape(block.to_string().trim()))
+                    .collect();
+            output += &format!("| {} |\n", cells.join(" | "));
+        }
+    }
+
+    let should_display_folder_path = should_display_folder_path(depth, metas);
+
+    for meta in metas {
+        if let Some(content) = &meta.content {
+            if should_display_folder_path {
+                output += &format!("\n**{}:**\n\n", escape(&meta.path.to_string_lossy()));
+            }
+
+            let display_option = DisplayOption::Relative {
+                base_path: &meta.path,
+            };
+
+            output += &render_tables(&display_option, content, flags, colors, icons, depth + 1);
+        }
+    }
+
+    output
+}
+
+fn render_tree(
+    metas: &[Meta],
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+    depth: usize,
+) -> String {
+    let mut output = String::new();
+
+    let padding_rules = get_padding_rules(metas, flags, &DisplayOption::FileName);
+
+    for meta in metas {
+        let blocks: Vec<String> = get_output(
+            meta,
+            colors,
+            icons,
+            flags,
+            &DisplayOption::FileName,
+            &padding_rules,
+        )
+        .iter()
+        .map(|block| escape(block.to_string().trim()))
+        .collect();
+
+        output += &format!("{}- {}\n", "  ".repeat(depth), blocks.join(" "));
+
+        if let Some(content) = &meta.content {
+            output += &render_tree(content, flags, colors, icons, depth + 1);
+        }
+    }
+
+    output
+}
+
+/// Escape the characters having a meaning in Markdown, and the line breaks
+/// which would end a table row.
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#' => {
+                output.push('\\');
+                output.push(c);
+            }
+            '\n' => output.push_str("<br>"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::{escape, render};
+    use crate::flags::{Block, Flags, Layout};
+    use crate::icon::{self, Icons};
+    use crate::meta::Meta;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!("a\\|b", escape("a|b"));
+        assert_eq!("\\_init\\_.py", escape("_init_.py"));
+        assert_eq!("two<br>lines", escape("two\nlines"));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        File::create(tmp_dir.path().join("file.txt")).expect("failed to create file");
+        let mut meta = Meta::from_path(tmp_dir.path()).unwrap();
+        let flags = Flags {
+            blocks: vec![Block::Size, Block::Name],
+            ..Flags::default()
+        };
+        meta.content = meta.recurse_into(1, &flags).unwrap();
+
+        assert_eq!(
+            "| Size | Name |\n| --- | --- |\n| 0 B | file.txt |\n",
+            render(&[meta], &flags, &Icons::new(icon::Theme::NoIcon))
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::create_dir(tmp_dir.path().join("dir")).expect("failed to create dir");
+        File::create(tmp_dir.path().join("dir/file.txt")).expect("failed to create file");
+        let flags = Flags {
+            blocks: vec![Block::Name],
+            layout: Layout::Tree,
+            ..Flags::default()
+        };
+        let mut meta = Meta::from_path(&tmp_dir.path().join("dir")).unwrap();
+        meta.content = meta.recurse_into(1, &flags).unwrap();
+
+        assert_eq!(
+            "- dir\n  - file.txt\n",
+            render(&[meta], &flags, &Icons::new(icon::Theme::NoIcon))
+        );
+    }
+}
@@ -0,0 +1,21 @@
+pub mod csv;
+pub mod html;
+pub mod json;
+pub mod markdown;
+pub mod ndjson;
+
+use crate::meta::FileType;
+
+/// Return the name given to a file type in the structured outputs.
+pub fn file_type_name(file_type: FileType) -> &'static str {
+    match file_type {
+        FileType::File { .. } => "file",
+        FileType::Directory { .. } => "directory",
+        FileType::SymLink => "symlink",
+        FileType::Pipe => "pipe",
+        FileType::Socket => "socket",
+        FileType::BlockDevice => "block-device",
+        FileType::CharDevice => "char-device",
+        FileType::Special => "special",
+    }
+}
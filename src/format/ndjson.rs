@@ -0,0 +1,57 @@
+use crate::flags::{Display, Flags, Layout};
+use crate::format::json::to_object;
+use crate::meta::Meta;
+use crate::{print_error, print_output};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Print one JSON object per line for each input and each entry found while
+/// walking the directories, as soon as it is read.
+///
+/// Nothing is kept in memory, so the entries are printed in the order of the
+/// file system rather than sorted, and the total sizes of the directories,
+/// only known after their content, are not available.
+pub fn stream(paths: Vec<PathBuf>, flags: &Flags) {
+    let depth = match flags.layout {
+        Layout::Tree => flags.recursion_depth,
+        _ if flags.recursive => flags.recursion_depth,
+        _ => 1,
+    };
+
+    for path in paths {
+        let meta = match Meta::from_path(&path) {
+            Ok(meta) => meta.with_details(flags),
+            Err(err) => {
+                print_error!("lsd: {}: {}\n", path.display(), err);
+                continue;
+            }
+        };
+
+        print_entry(&meta, None, 0, flags);
+
+        if flags.display == Display::DisplayDirectoryItself {
+            continue;
+        }
+
+        let mut visit = |entry: &Meta, parent: &Path, level: usize| {
+            print_entry(entry, Some(parent), level, flags)
+        };
+        if let Err(err) = meta.walk(depth, flags, &mut visit) {
+            print_error!("lsd: {}: {}\n", path.display(), err);
+        }
+    }
+}
+
+fn print_entry(meta: &Meta, parent: Option<&Path>, depth: usize, flags: &Flags) {
+    let mut object = to_object(meta, flags);
+
+    object.insert("depth".to_string(), Value::from(depth));
+    object.insert(
+        "parent".to_string(),
+        parent.map_or(Value::Null, |parent| {
+            Value::from(parent.to_string_lossy().as_ref())
+        }),
+    );
+
+    print_output!("{}\n", Value::Object(object));
+}
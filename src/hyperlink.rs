@@ -0,0 +1,107 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Wrap the text in an OSC 8 hyperlink pointing to the path.
+pub fn wrap(text: &str, path: &Path) -> String {
+    format!(
+        "\u{1b}]8;;{}\u{1b}\\{}\u{1b}]8;;\u{1b}\\",
+        file_url(&absolute_path(path)),
+        text
+    )
+}
+
+/// Return the absolute form of the path, without resolving the symlinks.
+pub fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match env::current_dir() {
+            Ok(dir) => dir.join(path),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+}
+
+/// Return the `file://hostname/path` URL of an absolute path.
+fn file_url(path: &Path) -> String {
+    let mut url = format!("file://{}", hostname());
+
+    #[cfg(windows)]
+    url.push('/');
+
+    for byte in path_bytes(path) {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            #[cfg(windows)]
+            b'\\' => url.push('/'),
+            #[cfg(windows)]
+            b':' => url.push(':'),
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    url
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(windows)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn hostname() -> String {
+    let mut buffer = [0u8; 256];
+
+    let result =
+        unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+    if result != 0 {
+        return String::new();
+    }
+
+    let len = buffer
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).to_string()
+}
+
+#[cfg(windows)]
+fn hostname() -> String {
+    env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{file_url, hostname, wrap};
+    use std::path::Path;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_file_url() {
+        assert_eq!(
+            format!("file://{}/tmp/a%20b/%C3%A9t%C3%A9%25.txt", hostname()),
+            file_url(Path::new("/tmp/a b/été%.txt"))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_wrap() {
+        assert_eq!(
+            format!(
+                "\u{1b}]8;;file://{}/tmp/file\u{1b}\\file\u{1b}]8;;\u{1b}\\",
+                hostname()
+            ),
+            wrap("file", Path::new("/tmp/file"))
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use crate::meta::{FileType, Name};
+use crate::theme::{FileTypeKey, IconSet};
 use std::collections::HashMap;
 
 pub struct Icons {
     display_icons: bool,
-    icons_by_name: HashMap<&'static str, &'static str>,
-    icons_by_extension: HashMap<&'static str, &'static str>,
-    default_folder_icon: &'static str,
-    default_file_icon: &'static str,
+    icons_by_name: HashMap<String, String>,
+    icons_by_extension: HashMap<String, String>,
+    icons_by_dir_name: HashMap<String, String>,
+    icons_by_file_type: HashMap<FileTypeKey, String>,
+    default_folder_icon: String,
+    default_file_icon: String,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -42,15 +45,47 @@ impl Icons {
                 )
             };
 
+        let to_owned = |icons: HashMap<&str, &str>| {
+            icons
+                .into_iter()
+                .map(|(key, icon)| (key.to_owned(), icon.to_owned()))
+                .collect()
+        };
+
         Self {
             display_icons,
-            icons_by_name,
-            icons_by_extension,
-            default_file_icon,
-            default_folder_icon,
+            icons_by_name: to_owned(icons_by_name),
+            icons_by_extension: to_owned(icons_by_extension),
+            icons_by_dir_name: HashMap::new(),
+            icons_by_file_type: Self::get_default_icons_by_file_type()
+                .into_iter()
+                .map(|(file_type, icon)| (file_type, icon.to_owned()))
+                .collect(),
+            default_file_icon: default_file_icon.to_owned(),
+            default_folder_icon: default_folder_icon.to_owned(),
         }
     }
 
+    /// Add the mappings of an icon set, replacing the existing ones on
+    /// conflict.
+    pub fn with_icon_set(mut self, set: IconSet) -> Self {
+        self.icons_by_name.extend(set.name);
+        self.icons_by_extension.extend(set.extension);
+        self.icons_by_dir_name.extend(set.dir_name);
+
+        for (file_type, icon) in set.file_type {
+            match file_type {
+                FileTypeKey::File => self.default_file_icon = icon,
+                FileTypeKey::Dir => self.default_folder_icon = icon,
+                _ => {
+                    self.icons_by_file_type.insert(file_type, icon);
+                }
+            }
+        }
+
+        self
+    }
+
     pub fn get(&self, name: &Name) -> String {
         if !self.display_icons {
             return String::new();
@@ -58,21 +93,22 @@ impl Icons {
 
         // Check file types
         let file_type: FileType = name.file_type();
+        let file_type_key = match file_type {
+            FileType::SymLink => Some(FileTypeKey::Symlink),
+            FileType::Socket => Some(FileTypeKey::Socket),
+            FileType::Pipe => Some(FileTypeKey::Pipe),
+            FileType::CharDevice => Some(FileTypeKey::CharDevice),
+            FileType::BlockDevice => Some(FileTypeKey::BlockDevice),
+            FileType::Special => Some(FileTypeKey::Special),
+            _ => None,
+        };
 
         let icon = if let FileType::Directory { .. } = file_type {
-            self.default_folder_icon
-        } else if let FileType::SymLink = file_type {
-            "\u{e27c}" // ""
-        } else if let FileType::Socket = file_type {
-            "\u{f6a7}" // ""
-        } else if let FileType::Pipe = file_type {
-            "\u{f731}" // ""
-        } else if let FileType::CharDevice = file_type {
-            "\u{e601}" // ""
-        } else if let FileType::BlockDevice = file_type {
-            "\u{fc29}" // "ﰩ"
-        } else if let FileType::Special = file_type {
-            "\u{f2dc}" // ""
+            self.icons_by_dir_name
+                .get(name.file_name())
+                .unwrap_or(&self.default_folder_icon)
+        } else if let Some(icon) = file_type_key.and_then(|key| self.icons_by_file_type.get(&key)) {
+            icon
         } else if let Some(icon) = self.icons_by_name.get(name.file_name()) {
             // Use the known names.
             icon
@@ -84,12 +120,25 @@ impl Icons {
             icon
         } else {
             // Use the default icons.
-            self.default_file_icon
+            &self.default_file_icon
         };
 
         format!("{}{}", icon, ICON_SPACE)
     }
 
+    fn get_default_icons_by_file_type() -> HashMap<FileTypeKey, &'static str> {
+        let mut m = HashMap::new();
+
+        m.insert(FileTypeKey::Symlink, "\u{e27c}"); // ""
+        m.insert(FileTypeKey::Socket, "\u{f6a7}"); // ""
+        m.insert(FileTypeKey::Pipe, "\u{f731}"); // ""
+        m.insert(FileTypeKey::CharDevice, "\u{e601}"); // ""
+        m.insert(FileTypeKey::BlockDevice, "\u{fc29}"); // "ﰩ"
+        m.insert(FileTypeKey::Special, "\u{f2dc}"); // ""
+
+        m
+    }
+
     fn get_default_icons_by_name() -> HashMap<&'static str, &'static str> {
         let mut m = HashMap::new();
 
@@ -332,7 +381,8 @@ impl Icons {
 mod test {
     use super::{Icons, Theme, ICON_SPACE};
     use crate::meta::Meta;
-    use std::fs::File;
+    use crate::theme::{FileTypeKey, IconSet};
+    use std::fs::{self, File};
     use tempfile::tempdir;
 
     #[test]
@@ -441,4 +491,39 @@ mod test {
             assert_eq!(icon, format!("{}{}", file_icon, ICON_SPACE));
         }
     }
+
+    #[test]
+    fn get_icon_from_icon_set() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let mut set = IconSet::default();
+        set.name.insert("BUILD".to_owned(), "B".to_owned());
+        set.extension.insert("proto".to_owned(), "P".to_owned());
+        set.extension.insert("rs".to_owned(), "R".to_owned());
+        set.dir_name.insert("src".to_owned(), "S".to_owned());
+        set.file_type.insert(FileTypeKey::File, "F".to_owned());
+        let icons = Icons::new(Theme::Fancy).with_icon_set(set);
+
+        for (file_name, file_icon) in &[
+            ("BUILD", "B"),
+            ("api.proto", "P"),
+            ("main.rs", "R"),
+            ("unknown", "F"),
+            ("README.md", "\u{f48a}"),
+        ] {
+            let file_path = tmp_dir.path().join(file_name);
+            File::create(&file_path).expect("failed to create file");
+            let meta = Meta::from_path(&file_path).unwrap();
+
+            assert_eq!(
+                icons.get(&meta.name),
+                format!("{}{}", file_icon, ICON_SPACE)
+            );
+        }
+
+        let dir_path = tmp_dir.path().join("src");
+        fs::create_dir(&dir_path).expect("failed to create dir");
+        let meta = Meta::from_path(&dir_path).unwrap();
+        assert_eq!(icons.get(&meta.name), format!("S{}", ICON_SPACE));
+    }
 }
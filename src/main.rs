@@ -11,29 +11,44 @@ extern crate ansi_term;
 extern crate chrono_humanize;
 extern crate libc;
 extern crate lscolors;
+extern crate serde;
+extern crate serde_json;
+extern crate serde_yaml;
 #[cfg(test)]
 extern crate tempfile;
 extern crate term_grid;
 extern crate terminal_size;
 extern crate time;
+extern crate toml;
 extern crate unicode_width;
 extern crate wild;
 
 #[cfg(unix)]
 extern crate users;
+#[cfg(unix)]
+extern crate xdg;
 
+#[cfg(windows)]
+extern crate dirs;
 #[cfg(windows)]
 extern crate winapi;
 
 mod app;
 mod color;
+mod config_file;
 mod core;
 mod display;
 mod flags;
+mod format;
+mod hyperlink;
 mod icon;
 mod meta;
+mod quoting;
 mod sort;
+mod summary;
+mod theme;
 
+use crate::config_file::Config;
 use crate::core::Core;
 use crate::flags::Flags;
 use std::path::PathBuf;
@@ -94,7 +109,8 @@ fn main() {
         .map(PathBuf::from)
         .collect();
 
-    let flags = Flags::from_matches(&matches).unwrap_or_else(|err| err.exit());
+    let config = Config::from_matches(&matches);
+    let flags = Flags::from_matches(&matches, &config).unwrap_or_else(|err| err.exit());
     let core = Core::new(flags);
 
     core.run(inputs);
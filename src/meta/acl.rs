@@ -0,0 +1,234 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::Flags;
+use crate::meta::xattr;
+use std::path::Path;
+
+const ACL_EA_VERSION: u32 = 0x0002;
+
+const ACL_USER_OBJ: u16 = 0x01;
+const ACL_USER: u16 = 0x02;
+const ACL_GROUP_OBJ: u16 = 0x04;
+const ACL_GROUP: u16 = 0x08;
+const ACL_MASK: u16 = 0x10;
+const ACL_OTHER: u16 = 0x20;
+
+/// An entry of a POSIX ACL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AclEntry {
+    tag: u16,
+    perm: u16,
+    id: u32,
+}
+
+/// The POSIX ACLs of a file, read from the `system.posix_acl_access` and
+/// `system.posix_acl_default` extended attributes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Acl {
+    access: Vec<AclEntry>,
+    default: Vec<AclEntry>,
+}
+
+impl From<&Path> for Acl {
+    fn from(path: &Path) -> Self {
+        let read = |name| {
+            xattr::get(path, name)
+                .and_then(|value| parse(&value))
+                .unwrap_or_default()
+        };
+
+        Self {
+            access: read("system.posix_acl_access"),
+            default: read("system.posix_acl_default"),
+        }
+    }
+}
+
+/// Parse the value of an ACL extended attribute: a version number followed
+/// by entries of a tag, a permission and an ID, all little-endian.
+fn parse(value: &[u8]) -> Option<Vec<AclEntry>> {
+    if value.len() < 4 {
+        return None;
+    }
+
+    let (version, entries) = value.split_at(4);
+    if u32::from_le_bytes([version[0], version[1], version[2], version[3]]) != ACL_EA_VERSION {
+        return None;
+    }
+
+    let chunks = entries.chunks_exact(8);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+
+    let entries = chunks
+        .map(|entry| AclEntry {
+            tag: u16::from_le_bytes([entry[0], entry[1]]),
+            perm: u16::from_le_bytes([entry[2], entry[3]]),
+            id: u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+        })
+        .collect();
+
+    Some(entries)
+}
+
+impl AclEntry {
+    fn to_string(&self, flags: &Flags) -> String {
+        let qualifier = match self.tag {
+            ACL_USER => user_name(self.id, flags),
+            ACL_GROUP => group_name(self.id, flags),
+            _ => String::new(),
+        };
+        let tag = match self.tag {
+            ACL_USER_OBJ | ACL_USER => "user",
+            ACL_GROUP_OBJ | ACL_GROUP => "group",
+            ACL_MASK => "mask",
+            ACL_OTHER => "other",
+            _ => "?",
+        };
+        let bit = |mask, chr| if self.perm & mask != 0 { chr } else { '-' };
+
+        format!(
+            "{}:{}:{}{}{}",
+            tag,
+            qualifier,
+            bit(4, 'r'),
+            bit(2, 'w'),
+            bit(1, 'x')
+        )
+    }
+}
+
+impl Acl {
+    /// Whether the ACLs give more than the permission bits, like the `+`
+    /// of `ls -l`.
+    pub fn is_extended(&self) -> bool {
+        self.access.len() > 3 || !self.default.is_empty()
+    }
+
+    pub fn render_indicator(&self, colors: &Colors) -> ColoredString<'_> {
+        if self.is_extended() {
+            colors.colorize(String::from("+"), &Elem::Acl)
+        } else {
+            ColoredString::from("")
+        }
+    }
+
+    /// Return the entries like `getfacl -c`, joined with commas, the ones of
+    /// the default ACL being prefixed with `default:`. Return `-` without
+    /// ACL.
+    pub fn acl_string(&self, flags: &Flags) -> String {
+        if !self.is_extended() {
+            return String::from("-");
+        }
+
+        self.access
+            .iter()
+            .map(|entry| entry.to_string(flags))
+            .chain(
+                self.default
+                    .iter()
+                    .map(|entry| format!("default:{}", entry.to_string(flags))),
+            )
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    pub fn render(&self, colors: &Colors, flags: &Flags) -> ColoredString<'_> {
+        colors.colorize(self.acl_string(flags), &Elem::Acl)
+    }
+}
+
+#[cfg(unix)]
+fn user_name(uid: u32, flags: &Flags) -> String {
+    match users::get_user_by_uid(uid) {
+        Some(user) if !flags.numeric_ids => user.name().to_string_lossy().to_string(),
+        _ => uid.to_string(),
+    }
+}
+
+#[cfg(unix)]
+fn group_name(gid: u32, flags: &Flags) -> String {
+    match users::get_group_by_gid(gid) {
+        Some(group) if !flags.numeric_ids => group.name().to_string_lossy().to_string(),
+        _ => gid.to_string(),
+    }
+}
+
+#[cfg(windows)]
+fn user_name(uid: u32, _: &Flags) -> String {
+    uid.to_string()
+}
+
+#[cfg(windows)]
+fn group_name(gid: u32, _: &Flags) -> String {
+    gid.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, Acl};
+    use crate::flags::Flags;
+
+    fn entry(tag: u16, perm: u16, id: u32) -> Vec<u8> {
+        let mut bytes = tag.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&perm.to_le_bytes());
+        bytes.extend_from_slice(&id.to_le_bytes());
+        bytes
+    }
+
+    fn acl_value(entries: &[(u16, u16, u32)]) -> Vec<u8> {
+        let mut value = 2u32.to_le_bytes().to_vec();
+        for (tag, perm, id) in entries {
+            value.extend(entry(*tag, *perm, *id));
+        }
+        value
+    }
+
+    #[test]
+    fn test_minimal_acl_is_not_extended() {
+        let acl = Acl {
+            access: parse(&acl_value(&[
+                (0x01, 6, u32::MAX),
+                (0x04, 4, u32::MAX),
+                (0x20, 4, u32::MAX),
+            ]))
+            .unwrap(),
+            default: vec![],
+        };
+
+        assert!(!acl.is_extended());
+        assert_eq!("-", acl.acl_string(&Flags::default()));
+    }
+
+    #[test]
+    fn test_extended_acl() {
+        let acl = Acl {
+            access: parse(&acl_value(&[
+                (0x01, 6, u32::MAX),
+                (0x02, 6, 4242),
+                (0x04, 5, u32::MAX),
+                (0x10, 7, u32::MAX),
+                (0x20, 4, u32::MAX),
+            ]))
+            .unwrap(),
+            default: parse(&acl_value(&[(0x08, 5, 4343)])).unwrap(),
+        };
+        let flags = Flags {
+            numeric_ids: true,
+            ..Flags::default()
+        };
+
+        assert!(acl.is_extended());
+        assert_eq!(
+            "user::rw-,user:4242:rw-,group::r-x,mask::rwx,other::r--,default:group:4343:r-x",
+            acl.acl_string(&flags)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_acl() {
+        assert_eq!(None, parse(&[2, 0, 0]));
+        assert_eq!(None, parse(&acl_value(&[(0x01, 6, 0)])[..10]));
+        assert_eq!(None, parse(&1u32.to_le_bytes()));
+    }
+}
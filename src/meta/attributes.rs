@@ -0,0 +1,71 @@
+use crate::color::{ColoredString, Colors, Elem};
+use ansi_term::ANSIStrings;
+use std::fs::Metadata;
+
+/// The attributes of a file on Windows: archive, read-only, hidden and system.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Attributes {
+    bits: Option<u32>,
+}
+
+const ATTRIBUTES: [(u32, &str); 4] = [
+    (0x20, "a"), // FILE_ATTRIBUTE_ARCHIVE
+    (0x01, "r"), // FILE_ATTRIBUTE_READONLY
+    (0x02, "h"), // FILE_ATTRIBUTE_HIDDEN
+    (0x04, "s"), // FILE_ATTRIBUTE_SYSTEM
+];
+
+impl From<&Metadata> for Attributes {
+    #[cfg(windows)]
+    fn from(meta: &Metadata) -> Self {
+        use std::os::windows::fs::MetadataExt;
+
+        Self {
+            bits: Some(meta.file_attributes()),
+        }
+    }
+
+    #[cfg(unix)]
+    fn from(_: &Metadata) -> Self {
+        Self { bits: None }
+    }
+}
+
+impl Attributes {
+    /// Render the attributes as `arhs`, or None when the platform has none.
+    pub fn render(&self, colors: &Colors) -> Option<ColoredString<'_>> {
+        let bits = self.bits?;
+        let strings: Vec<ColoredString> = ATTRIBUTES
+            .iter()
+            .map(|(bit, chr)| {
+                if bits & bit == *bit {
+                    colors.colorize(String::from(*chr), &Elem::Read)
+                } else {
+                    colors.colorize(String::from("-"), &Elem::NoAccess)
+                }
+            })
+            .collect();
+
+        Some(ColoredString::from(ANSIStrings(&strings).to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Attributes;
+    use crate::color::{Colors, Theme};
+
+    #[test]
+    fn test_render_attributes() {
+        let attributes = Attributes {
+            bits: Some(0x20 | 0x02),
+        };
+        let colors = Colors::new(Theme::NoColor);
+
+        assert_eq!(
+            Some("a-h-".to_string()),
+            attributes.render(&colors).map(|s| s.to_string())
+        );
+        assert_eq!(None, Attributes { bits: None }.render(&colors));
+    }
+}
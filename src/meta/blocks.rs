@@ -0,0 +1,115 @@
+use std::fs::Metadata;
+
+/// The number of 512-byte blocks allocated to the file (`st_blocks`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Blocks {
+    count: Option<u64>,
+}
+
+impl From<&Metadata> for Blocks {
+    #[cfg(unix)]
+    fn from(meta: &Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        Self {
+            count: Some(meta.blocks()),
+        }
+    }
+
+    #[cfg(windows)]
+    fn from(_: &Metadata) -> Self {
+        Self { count: None }
+    }
+}
+
+impl Blocks {
+    pub fn new(count: Option<u64>) -> Self {
+        Self { count }
+    }
+
+    pub fn count(&self) -> Option<u64> {
+        self.count
+    }
+
+    /// Return the allocated size in bytes, 0 when unknown.
+    pub fn bytes(&self) -> u64 {
+        self.count.unwrap_or(0) * 512
+    }
+
+    /// Return the allocated size in bytes, or the length of the file on the
+    /// platforms which don't report the blocks.
+    pub fn allocated_bytes(&self, len: u64) -> u64 {
+        match self.count {
+            Some(_) => self.bytes(),
+            None => len,
+        }
+    }
+
+    /// Whether a file of the given length has much less space allocated
+    /// than its length, because of holes or compression.
+    pub fn is_sparse(&self, len: u64) -> bool {
+        match self.count {
+            Some(_) => {
+                let allocated = self.bytes();
+                allocated < len / 2 && len - allocated >= 4096
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::Blocks;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_blocks_of_empty_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("empty");
+        File::create(&path).expect("failed to create file");
+
+        let blocks = Blocks::from(&fs::metadata(&path).unwrap());
+        assert_eq!(Some(0), blocks.count());
+    }
+
+    #[test]
+    fn test_blocks_of_written_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        let mut file = File::create(&path).expect("failed to create file");
+        file.write_all(&[1; 8192]).expect("failed to write file");
+        file.sync_all().expect("failed to sync file");
+
+        let blocks = Blocks::from(&fs::metadata(&path).unwrap());
+        assert!(blocks.bytes() >= 8192);
+    }
+
+    #[test]
+    fn test_sparse_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("sparse");
+        let file = File::create(&path).expect("failed to create file");
+        file.set_len(1024 * 1024).expect("failed to extend file");
+
+        let metadata = fs::metadata(&path).unwrap();
+        let blocks = Blocks::from(&metadata);
+        assert!(blocks.is_sparse(metadata.len()));
+    }
+
+    #[test]
+    fn test_written_file_is_not_sparse() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        let mut file = File::create(&path).expect("failed to create file");
+        file.write_all(&[1; 8192]).expect("failed to write file");
+        file.sync_all().expect("failed to sync file");
+
+        let metadata = fs::metadata(&path).unwrap();
+        let blocks = Blocks::from(&metadata);
+        assert!(!blocks.is_sparse(metadata.len()));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::meta::xattr;
+use std::path::Path;
+
+const VFS_CAP_REVISION_MASK: u32 = 0xFF00_0000;
+const VFS_CAP_REVISION_2: u32 = 0x0200_0000;
+const VFS_CAP_REVISION_3: u32 = 0x0300_0000;
+const VFS_CAP_FLAGS_EFFECTIVE: u32 = 0x0000_0001;
+
+/// The names of the capabilities, indexed by their number.
+const CAPABILITIES: [&str; 41] = [
+    "cap_chown",
+    "cap_dac_override",
+    "cap_dac_read_search",
+    "cap_fowner",
+    "cap_fsetid",
+    "cap_kill",
+    "cap_setgid",
+    "cap_setuid",
+    "cap_setpcap",
+    "cap_linux_immutable",
+    "cap_net_bind_service",
+    "cap_net_broadcast",
+    "cap_net_admin",
+    "cap_net_raw",
+    "cap_ipc_lock",
+    "cap_ipc_owner",
+    "cap_sys_module",
+    "cap_sys_rawio",
+    "cap_sys_chroot",
+    "cap_sys_ptrace",
+    "cap_sys_pacct",
+    "cap_sys_admin",
+    "cap_sys_boot",
+    "cap_sys_nice",
+    "cap_sys_resource",
+    "cap_sys_time",
+    "cap_sys_tty_config",
+    "cap_mknod",
+    "cap_lease",
+    "cap_audit_write",
+    "cap_audit_control",
+    "cap_setfcap",
+    "cap_mac_override",
+    "cap_mac_admin",
+    "cap_syslog",
+    "cap_wake_alarm",
+    "cap_block_suspend",
+    "cap_audit_read",
+    "cap_perfmon",
+    "cap_bpf",
+    "cap_checkpoint_restore",
+];
+
+/// The file capabilities of an executable, read from its `security.capability`
+/// extended attribute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    permitted: u64,
+    inheritable: u64,
+    effective: bool,
+}
+
+impl From<&Path> for Capabilities {
+    fn from(path: &Path) -> Self {
+        xattr::get(path, "security.capability")
+            .and_then(|value| parse(&value))
+            .unwrap_or_default()
+    }
+}
+
+/// Parse the value of the capability extended attribute: the revision and the
+/// flags, then the low and the high words of the permitted and inheritable
+/// sets, all little-endian. The revision 3 adds the root ID of the user
+/// namespace, which is not shown.
+fn parse(value: &[u8]) -> Option<Capabilities> {
+    let words: Vec<u32> = value
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect();
+
+    let magic = *words.first()?;
+    let expected_len = match magic & VFS_CAP_REVISION_MASK {
+        VFS_CAP_REVISION_2 => 5,
+        VFS_CAP_REVISION_3 => 6,
+        _ => return None,
+    };
+    if value.len() != expected_len * 4 {
+        return None;
+    }
+
+    Some(Capabilities {
+        permitted: u64::from(words[1]) | (u64::from(words[3]) << 32),
+        inheritable: u64::from(words[2]) | (u64::from(words[4]) << 32),
+        effective: magic & VFS_CAP_FLAGS_EFFECTIVE != 0,
+    })
+}
+
+/// Return the name of a capability, `cap_<number>` for the ones unknown here
+/// as `getcap` does.
+fn capability_name(number: usize) -> String {
+    match CAPABILITIES.get(number) {
+        Some(name) => name.to_string(),
+        None => format!("cap_{}", number),
+    }
+}
+
+impl Capabilities {
+    pub fn is_empty(&self) -> bool {
+        self.permitted == 0 && self.inheritable == 0
+    }
+
+    /// Return the capabilities like `getcap`, e.g. `cap_net_raw+ep`: the ones
+    /// with the same flags are grouped and the groups are separated by
+    /// spaces. Return `-` without capabilities.
+    pub fn capabilities_string(&self) -> String {
+        if self.is_empty() {
+            return String::from("-");
+        }
+
+        let mut groups: Vec<(String, Vec<String>)> = vec![];
+        for number in 0..64 {
+            let bit = 1u64 << number;
+            let permitted = self.permitted & bit != 0;
+            let inheritable = self.inheritable & bit != 0;
+            if !permitted && !inheritable {
+                continue;
+            }
+
+            let mut flags = String::new();
+            if self.effective {
+                flags.push('e');
+            }
+            if inheritable {
+                flags.push('i');
+            }
+            if permitted {
+                flags.push('p');
+            }
+
+            match groups
+                .iter_mut()
+                .find(|(group_flags, _)| *group_flags == flags)
+            {
+                Some((_, names)) => names.push(capability_name(number)),
+                None => groups.push((flags, vec![capability_name(number)])),
+            }
+        }
+
+        groups
+            .iter()
+            .map(|(flags, names)| format!("{}+{}", names.join(","), flags))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString<'_> {
+        let elem = if self.is_empty() {
+            Elem::NoAccess
+        } else {
+            Elem::File {
+                exec: true,
+                uid: true,
+            }
+        };
+
+        colors.colorize(self.capabilities_string(), &elem)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, Capabilities};
+    use crate::color::{Colors, Elem, Theme};
+
+    fn capability_value(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_v2_capabilities() {
+        // The attribute set by `setcap cap_net_raw+ep`.
+        let capabilities = parse(&capability_value(&[0x0200_0001, 1 << 13, 0, 0, 0])).unwrap();
+
+        assert_eq!("cap_net_raw+ep", capabilities.capabilities_string());
+    }
+
+    #[test]
+    fn test_parse_v3_capabilities() {
+        let capabilities = parse(&capability_value(&[
+            0x0300_0000,
+            1 << 10 | 1 << 13,
+            1 << 13,
+            1 << 9,
+            0,
+            1000,
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            "cap_net_bind_service,cap_41+p cap_net_raw+ip",
+            capabilities.capabilities_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_capabilities() {
+        assert_eq!(None, parse(&[2, 0, 0]));
+        assert_eq!(None, parse(&capability_value(&[0x0200_0000, 0, 0])));
+        assert_eq!(None, parse(&capability_value(&[0x0100_0000, 0, 0])));
+        assert_eq!("-", Capabilities::default().capabilities_string());
+    }
+
+    #[test]
+    fn test_render_without_capabilities() {
+        let colors = Colors::new(Theme::NoLscolors);
+
+        // No privileged style for the placeholder.
+        assert_eq!(
+            colors.colorize(String::from("-"), &Elem::NoAccess),
+            Capabilities::default().render(&colors)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::meta::xattr;
+use std::path::Path;
+
+/// The SELinux security context of a file, read from its `security.selinux`
+/// extended attribute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Context {
+    label: Option<String>,
+}
+
+impl From<&Path> for Context {
+    fn from(path: &Path) -> Self {
+        let label = xattr::get(path, "security.selinux").map(|value| {
+            // The kernel ends the label with a NUL character.
+            let value = value.strip_suffix(&[0]).unwrap_or(&value);
+            String::from_utf8_lossy(value).to_string()
+        });
+
+        Self { label }
+    }
+}
+
+impl Context {
+    /// Return the `user:role:type:level` label, or `?` for the files which
+    /// have none.
+    pub fn context_string(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => String::from("?"),
+        }
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString<'_> {
+        colors.colorize(self.context_string(), &Elem::Context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Context;
+
+    #[test]
+    fn test_context_string() {
+        let context = Context {
+            label: Some("system_u:object_r:etc_t:s0".to_string()),
+        };
+        assert_eq!("system_u:object_r:etc_t:s0", context.context_string());
+
+        assert_eq!("?", Context::default().context_string());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_context_of_labeled_file() {
+        use std::ffi::CString;
+        use std::fs::File;
+        use std::os::unix::ffi::OsStrExt;
+        use tempfile::tempdir;
+
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+
+        // Setting the label needs privileges, and a label known by the
+        // policy on the systems running SELinux.
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let c_name = CString::new("security.selinux").unwrap();
+        let value = b"system_u:object_r:tmp_t:s0\0";
+        let result = unsafe {
+            libc::setxattr(
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                value.as_ptr() as *const libc::c_void,
+                value.len(),
+                0,
+            )
+        };
+        if result != 0 {
+            return;
+        }
+
+        assert_eq!(
+            "system_u:object_r:tmp_t:s0",
+            Context::from(path.as_path()).context_string()
+        );
+    }
+}
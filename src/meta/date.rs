@@ -2,7 +2,7 @@ use crate::color::{ColoredString, Colors, Elem};
 use crate::flags::{DateFlag, Flags};
 use chrono_humanize::HumanTime;
 use std::fs::Metadata;
-use std::time::UNIX_EPOCH;
+use std::time::{SystemTime, UNIX_EPOCH};
 use time::{Duration, Timespec};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -12,12 +12,17 @@ impl<'a> From<&'a Metadata> for Date {
     fn from(meta: &'a Metadata) -> Self {
         let modified_time = meta.modified().expect("failed to retrieve modified date");
 
-        let modified_time_since_epoch =
-            modified_time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Date::from(modified_time)
+    }
+}
+
+impl From<SystemTime> for Date {
+    fn from(system_time: SystemTime) -> Self {
+        let time_since_epoch = system_time.duration_since(UNIX_EPOCH).unwrap_or_default();
 
         let time = time::at(Timespec::new(
-            modified_time_since_epoch.as_secs() as i64,
-            modified_time_since_epoch.subsec_nanos() as i32,
+            time_since_epoch.as_secs() as i64,
+            time_since_epoch.subsec_nanos() as i32,
         ));
 
         Date(time)
@@ -25,6 +30,39 @@ impl<'a> From<&'a Metadata> for Date {
 }
 
 impl Date {
+    /// Return the time of the last access to the file.
+    pub fn accessed(meta: &Metadata) -> Option<Self> {
+        meta.accessed().ok().map(Date::from)
+    }
+
+    /// Return the time of the last change of the file status (`st_ctime`).
+    #[cfg(unix)]
+    pub fn changed(meta: &Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        Some(Date(time::at(Timespec::new(
+            meta.ctime(),
+            meta.ctime_nsec() as i32,
+        ))))
+    }
+
+    #[cfg(windows)]
+    pub fn changed(_: &Metadata) -> Option<Self> {
+        None
+    }
+
+    /// Return the birth time of the file. On Linux, the standard library reads
+    /// it with `statx`, so it is missing on the kernels and the filesystems
+    /// which don't record it.
+    pub fn created(meta: &Metadata) -> Option<Self> {
+        meta.created().ok().map(Date::from)
+    }
+
+    /// Render an unknown date.
+    pub fn render_missing(colors: &Colors) -> ColoredString<'_> {
+        colors.colorize(String::from("-"), &Elem::Older)
+    }
+
     pub fn render(&self, colors: &Colors, flags: &Flags) -> ColoredString {
         let now = time::now();
 
@@ -47,6 +85,11 @@ impl Date {
             DateFlag::Formatted(format) => self.0.to_local().strftime(&format).unwrap().to_string(),
         }
     }
+
+    /// Return the date in the ISO 8601 format, e.g. `2020-04-09T19:15:32+02:00`.
+    pub fn iso_string(&self) -> String {
+        self.0.to_local().rfc3339().to_string()
+    }
 }
 
 #[cfg(test)]
@@ -167,6 +210,36 @@ mod test {
         fs::remove_file(file_path).unwrap();
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_access_time() {
+        let mut file_path = env::temp_dir();
+        file_path.push("test_access_time.tmp");
+
+        let access_date = (time::now() - time::Duration::days(3)).to_local();
+        fs::write(&file_path, "").unwrap();
+        let success = Command::new("touch")
+            .arg("-a")
+            .arg("-t")
+            .arg(access_date.strftime("%Y%m%d%H%M.%S").unwrap().to_string())
+            .arg(&file_path)
+            .status()
+            .unwrap()
+            .success();
+        assert!(success, "failed to exec touch");
+
+        let metadata = file_path.metadata().unwrap();
+        let flags = Flags::default();
+
+        assert_eq!(
+            access_date.ctime().to_string(),
+            Date::accessed(&metadata).unwrap().date_string(&flags)
+        );
+        assert!(Date::changed(&metadata).is_some());
+
+        fs::remove_file(file_path).unwrap();
+    }
+
     #[test]
     fn test_with_relative_date() {
         let mut file_path = env::temp_dir();
@@ -0,0 +1,86 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::fs::Metadata;
+
+/// The major and minor numbers of a block or character device (`st_rdev`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Device {
+    numbers: Option<(u64, u64)>,
+}
+
+impl From<&Metadata> for Device {
+    #[cfg(unix)]
+    fn from(meta: &Metadata) -> Self {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let file_type = meta.file_type();
+        if !file_type.is_block_device() && !file_type.is_char_device() {
+            return Self { numbers: None };
+        }
+
+        let rdev = meta.rdev() as libc::dev_t;
+        Self {
+            numbers: Some((libc::major(rdev) as u64, libc::minor(rdev) as u64)),
+        }
+    }
+
+    #[cfg(windows)]
+    fn from(_: &Metadata) -> Self {
+        Self { numbers: None }
+    }
+}
+
+impl Device {
+    pub fn numbers(&self) -> Option<(u64, u64)> {
+        self.numbers
+    }
+
+    /// Return the `major, minor` string, with the minor number right-aligned
+    /// on the given width.
+    pub fn value_string(&self, minor_alignment: usize) -> String {
+        match self.numbers {
+            Some((major, minor)) => {
+                format!("{}, {:>width$}", major, minor, width = minor_alignment)
+            }
+            None => String::new(),
+        }
+    }
+
+    pub fn render(
+        &self,
+        colors: &Colors,
+        minor_alignment: usize,
+        alignment: usize,
+    ) -> ColoredString<'_> {
+        let content = format!(
+            "{:>width$}",
+            self.value_string(minor_alignment),
+            width = alignment
+        );
+
+        colors.colorize(content, &Elem::NonFile)
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::Device;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_device_numbers_of_null() {
+        let device = Device::from(&fs::metadata("/dev/null").unwrap());
+
+        assert_eq!(Some((1, 3)), device.numbers());
+        assert_eq!("1,   3", device.value_string(3));
+    }
+
+    #[test]
+    fn test_regular_file_has_no_device_numbers() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let device = Device::from(&fs::metadata(tmp_dir.path()).unwrap());
+        assert_eq!(None, device.numbers());
+    }
+}
@@ -23,6 +23,10 @@ impl<'a> From<&'a Metadata> for INode {
 }
 
 impl INode {
+    pub fn index(&self) -> Option<u64> {
+        self.index
+    }
+
     pub fn render(&self, colors: &Colors) -> ColoredString {
         match self.index {
             Some(i) => colors.colorize(i.to_string(), &Elem::INode { valid: true }),
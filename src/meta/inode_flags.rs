@@ -0,0 +1,152 @@
+use crate::color::{ColoredString, Colors, Elem, Theme};
+use ansi_term::ANSIStrings;
+use std::path::Path;
+
+/// The inode flags of a file on Linux, as shown by `lsattr`.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct InodeFlags {
+    bits: Option<u32>,
+}
+
+/// The inode flags, in the order `lsattr` prints them.
+const INODE_FLAGS: [(u32, &str); 22] = [
+    (0x0000_0001, "s"), // FS_SECRM_FL
+    (0x0000_0002, "u"), // FS_UNRM_FL
+    (0x0000_0008, "S"), // FS_SYNC_FL
+    (0x0001_0000, "D"), // FS_DIRSYNC_FL
+    (FS_IMMUTABLE_FL, "i"),
+    (0x0000_0020, "a"), // FS_APPEND_FL
+    (0x0000_0040, "d"), // FS_NODUMP_FL
+    (0x0000_0080, "A"), // FS_NOATIME_FL
+    (0x0000_0004, "c"), // FS_COMPR_FL
+    (0x0000_0800, "E"), // FS_ENCRYPT_FL
+    (0x0000_4000, "j"), // FS_JOURNAL_DATA_FL
+    (0x0000_1000, "I"), // FS_INDEX_FL
+    (0x0000_8000, "t"), // FS_NOTAIL_FL
+    (0x0002_0000, "T"), // FS_TOPDIR_FL
+    (0x0008_0000, "e"), // FS_EXTENT_FL
+    (0x0080_0000, "C"), // FS_NOCOW_FL
+    (0x0200_0000, "x"), // FS_DAX_FL
+    (0x4000_0000, "F"), // FS_CASEFOLD_FL
+    (0x1000_0000, "N"), // FS_INLINE_DATA_FL
+    (0x2000_0000, "P"), // FS_PROJINHERIT_FL
+    (0x0010_0000, "V"), // FS_VERITY_FL
+    (0x0000_0400, "m"), // FS_NOCOMP_FL
+];
+
+const FS_IMMUTABLE_FL: u32 = 0x0000_0010;
+
+impl From<&Path> for InodeFlags {
+    #[cfg(target_os = "linux")]
+    fn from(path: &Path) -> Self {
+        Self {
+            bits: read_flags(path),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn from(_: &Path) -> Self {
+        Self::default()
+    }
+}
+
+impl From<u32> for InodeFlags {
+    fn from(bits: u32) -> Self {
+        Self { bits: Some(bits) }
+    }
+}
+
+impl InodeFlags {
+    /// Whether the file has the immutable flag, i.e. cannot be modified,
+    /// renamed nor deleted, even by root.
+    pub fn is_immutable(&self) -> bool {
+        matches!(self.bits, Some(bits) if bits & FS_IMMUTABLE_FL != 0)
+    }
+
+    /// Render the flags like `lsattr`, or None when they are unknown.
+    pub fn render(&self, colors: &Colors) -> Option<ColoredString<'_>> {
+        let bits = self.bits?;
+        let strings: Vec<ColoredString> = INODE_FLAGS
+            .iter()
+            .map(|(bit, chr)| {
+                if bits & bit == *bit {
+                    colors.colorize(String::from(*chr), &Elem::Read)
+                } else {
+                    colors.colorize(String::from("-"), &Elem::NoAccess)
+                }
+            })
+            .collect();
+
+        Some(ColoredString::from(ANSIStrings(&strings).to_string()))
+    }
+
+    /// Render the flags for the attributes block, `-` when they are unknown,
+    /// e.g. on the filesystems which don't support them.
+    pub fn render_block(&self, colors: &Colors) -> ColoredString<'_> {
+        self.render(colors)
+            .unwrap_or_else(|| colors.colorize(String::from("-"), &Elem::NoAccess))
+    }
+
+    /// Return the flags without colors, `-` when they are unknown.
+    pub fn flags_string(&self) -> String {
+        self.render(&Colors::new(Theme::NoColor))
+            .map_or_else(|| String::from("-"), |s| s.to_string())
+    }
+}
+
+/// Read the inode flags with the `FS_IOC_GETFLAGS` ioctl, as `lsattr` does.
+#[cfg(target_os = "linux")]
+fn read_flags(path: &Path) -> Option<u32> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let fd = unsafe {
+        libc::open(
+            c_path.as_ptr(),
+            libc::O_RDONLY | libc::O_NONBLOCK | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+
+    // The kernel reads and writes an int, whatever the size of the argument
+    // declared by the ioctl number.
+    let mut flags: libc::c_int = 0;
+    let result = unsafe { libc::ioctl(fd, libc::FS_IOC_GETFLAGS as _, &mut flags) };
+    unsafe { libc::close(fd) };
+
+    if result < 0 {
+        None
+    } else {
+        Some(flags as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InodeFlags;
+    use crate::color::{Colors, Theme};
+
+    #[test]
+    fn test_render_inode_flags() {
+        let flags = InodeFlags::from(0x10 | 0x80 | 0x80000);
+        let colors = Colors::new(Theme::NoColor);
+
+        assert_eq!(
+            Some("----i--A------e-------".to_string()),
+            flags.render(&colors).map(|s| s.to_string())
+        );
+        assert!(flags.is_immutable());
+        assert_eq!(None, InodeFlags::default().render(&colors));
+    }
+
+    #[test]
+    fn test_unknown_inode_flags() {
+        let flags = InodeFlags::default();
+
+        assert!(!flags.is_immutable());
+        assert_eq!("-", flags.flags_string());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::fs::Metadata;
+
+/// The number of hard links to the file (`st_nlink`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Links {
+    nlink: Option<u64>,
+    is_file: bool,
+}
+
+impl From<&Metadata> for Links {
+    #[cfg(unix)]
+    fn from(meta: &Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        Self {
+            nlink: Some(meta.nlink()),
+            is_file: meta.is_file(),
+        }
+    }
+
+    #[cfg(windows)]
+    fn from(meta: &Metadata) -> Self {
+        Self {
+            nlink: None,
+            is_file: meta.is_file(),
+        }
+    }
+}
+
+impl Links {
+    pub fn nlink(&self) -> Option<u64> {
+        self.nlink
+    }
+
+    /// Whether the file is a regular file reachable through other names.
+    pub fn is_shared(&self) -> bool {
+        self.is_file && matches!(self.nlink, Some(nlink) if nlink > 1)
+    }
+
+    pub fn value_string(&self) -> String {
+        match self.nlink {
+            Some(nlink) => nlink.to_string(),
+            None => String::from("-"),
+        }
+    }
+
+    pub fn render(&self, colors: &Colors, padding: usize) -> ColoredString<'_> {
+        let content = format!("{:>width$}", self.value_string(), width = padding);
+
+        colors.colorize(
+            content,
+            &Elem::Links {
+                multiple: self.is_shared(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::Links;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_links_of_single_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+
+        let links = Links::from(&fs::metadata(&path).unwrap());
+        assert_eq!(Some(1), links.nlink());
+        assert!(!links.is_shared());
+    }
+
+    #[test]
+    fn test_links_of_hard_linked_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+        fs::hard_link(&path, tmp_dir.path().join("link")).expect("failed to create hard link");
+
+        let links = Links::from(&fs::metadata(&path).unwrap());
+        assert_eq!(Some(2), links.nlink());
+        assert!(links.is_shared());
+    }
+
+    #[test]
+    fn test_links_of_directory_are_not_shared() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::create_dir(tmp_dir.path().join("dir")).expect("failed to create dir");
+
+        let links = Links::from(&fs::metadata(tmp_dir.path()).unwrap());
+        assert!(links.nlink().unwrap() > 1);
+        assert!(!links.is_shared());
+    }
+}
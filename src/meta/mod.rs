@@ -1,47 +1,86 @@
+mod acl;
+mod attributes;
+mod blocks;
+mod capabilities;
+mod context;
 mod date;
+mod device;
 mod filetype;
 mod indicator;
 mod inode;
+mod inode_flags;
+mod links;
 pub mod name;
 mod owner;
 mod permissions;
 mod size;
 mod symlink;
+pub mod xattr;
 
 #[cfg(windows)]
 mod windows_utils;
 
+pub use self::acl::Acl;
+pub use self::attributes::Attributes;
+pub use self::blocks::Blocks;
+pub use self::capabilities::Capabilities;
+pub use self::context::Context;
 pub use self::date::Date;
+pub use self::device::Device;
 pub use self::filetype::FileType;
 pub use self::indicator::Indicator;
 pub use self::inode::INode;
+pub use self::inode_flags::InodeFlags;
+pub use self::links::Links;
 pub use self::name::Name;
 pub use self::owner::Owner;
 pub use self::permissions::Permissions;
 pub use self::size::Size;
 pub use self::symlink::SymLink;
+pub use self::xattr::Xattrs;
 pub use crate::flags::Display;
+use crate::flags::{Block, Flags, PermissionFlag, SizeKind, TimeFlag, WhenFlag};
 pub use crate::icon::Icons;
 use crate::print_error;
 
+use std::ffi::OsStr;
 use std::fs::read_link;
 use std::io::{Error, ErrorKind};
 use std::path::{Component, Path, PathBuf};
 
 use globset::GlobSet;
 
+/// The reasons for an entry to be left out of the listing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Skipped {
+    Hidden,
+    Ignored,
+}
+
 #[derive(Clone, Debug)]
 pub struct Meta {
     pub name: Name,
     pub path: PathBuf,
     pub permissions: Permissions,
+    pub attributes: Attributes,
+    pub inode_flags: InodeFlags,
+    pub xattrs: Xattrs,
+    pub acl: Acl,
+    pub context: Context,
+    pub capabilities: Capabilities,
     pub date: Date,
+    pub accessed: Option<Date>,
+    pub changed: Option<Date>,
+    pub created: Option<Date>,
     pub owner: Owner,
     pub file_type: FileType,
     pub size: Size,
+    pub blocks: Blocks,
+    pub device: Device,
     pub symlink: SymLink,
     pub indicator: Indicator,
     pub inode: INode,
+    pub links: Links,
     pub content: Option<Vec<Meta>>,
 }
 
@@ -49,42 +88,112 @@ impl Meta {
     pub fn recurse_into(
         &self,
         depth: usize,
-        display: Display,
-        ignore_globs: &GlobSet,
+        flags: &Flags,
     ) -> Result<Option<Vec<Meta>>, std::io::Error> {
         if depth == 0 {
             return Ok(None);
         }
 
+        let mut content: Vec<Meta> = Vec::new();
+
+        let listed = self.visit_entries(flags, |mut entry_meta, implied| {
+            if !implied {
+                match entry_meta.recurse_into(depth - 1, flags) {
+                    Ok(content) => entry_meta.content = content,
+                    Err(err) => {
+                        print_error!("lsd: {}: {}\n", entry_meta.path.display(), err);
+                        return;
+                    }
+                };
+            }
+
+            content.push(entry_meta);
+        })?;
+
+        if listed {
+            Ok(Some(content))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Walk the directory the same way as `recurse_into`, but hand each entry
+    /// to `visit` as soon as it is read instead of collecting them. `visit`
+    /// receives the entry, the path of its parent and its depth, starting at
+    /// 1 for the direct entries of this directory.
+    pub fn walk<F>(&self, depth: usize, flags: &Flags, visit: &mut F) -> Result<(), std::io::Error>
+    where
+        F: FnMut(&Meta, &Path, usize),
+    {
+        self.walk_at(1, depth, flags, visit)
+    }
+
+    fn walk_at<F>(
+        &self,
+        level: usize,
+        depth: usize,
+        flags: &Flags,
+        visit: &mut F,
+    ) -> Result<(), std::io::Error>
+    where
+        F: FnMut(&Meta, &Path, usize),
+    {
+        if depth == 0 {
+            return Ok(());
+        }
+
+        self.visit_entries(flags, |entry_meta, implied| {
+            visit(&entry_meta, &self.path, level);
+
+            if !implied {
+                if let Err(err) = entry_meta.walk_at(level + 1, depth - 1, flags, visit) {
+                    print_error!("lsd: {}: {}\n", entry_meta.path.display(), err);
+                }
+            }
+        })?;
+
+        Ok(())
+    }
+
+    /// Read the entries of the directory and hand them to `visit`, skipping
+    /// the hidden and ignored ones. The implied `.` and `..` entries are
+    /// flagged so that they are not recursed into.
+    ///
+    /// Return false if this is not a directory or if it can't be read.
+    fn visit_entries<F>(&self, flags: &Flags, mut visit: F) -> Result<bool, std::io::Error>
+    where
+        F: FnMut(Meta, bool),
+    {
+        let display = flags.display;
         if display == Display::DisplayDirectoryItself {
-            return Ok(None);
+            return Ok(false);
         }
 
         match self.file_type {
             FileType::Directory { .. } => (),
-            _ => return Ok(None),
+            _ => return Ok(false),
         }
 
         let entries = match self.path.read_dir() {
             Ok(entries) => entries,
             Err(err) => {
                 print_error!("lsd: {}: {}\n", self.path.display(), err);
-                return Ok(None);
+                return Ok(false);
             }
         };
 
-        let mut content: Vec<Meta> = Vec::new();
-
         if let Display::DisplayAll = display {
             let mut current_meta;
 
             current_meta = self.clone();
             current_meta.name.name = ".".to_owned();
+            current_meta.content = None;
 
-            let parent_meta = Self::from_path(&self.path.join(Component::ParentDir))?;
+            let parent_meta =
+                Self::from_path(&self.path.join(Component::ParentDir))?.with_details(flags);
 
-            content.push(current_meta);
-            content.push(parent_meta);
+            visit(current_meta, true);
+            visit(parent_meta, true);
         }
 
         for entry in entries {
@@ -94,55 +203,128 @@ impl Meta {
                 .file_name()
                 .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "invalid file name"))?;
 
-            if ignore_globs.is_match(&name) {
+            if Self::skip_reason(name, display, &flags.ignore_globs).is_some() {
                 continue;
             }
 
-            if let Display::DisplayOnlyVisible = display {
-                if name.to_string_lossy().starts_with('.') {
-                    continue;
-                }
-            }
-
-            let mut entry_meta = match Self::from_path(&path) {
-                Ok(res) => res,
+            let entry_meta = match Self::from_path(&path) {
+                Ok(res) => res.with_details(flags),
                 Err(err) => {
                     print_error!("lsd: {}: {}\n", path.display(), err);
                     continue;
                 }
             };
 
-            match entry_meta.recurse_into(depth - 1, display, ignore_globs) {
-                Ok(content) => entry_meta.content = content,
-                Err(err) => {
-                    print_error!("lsd: {}: {}\n", path.display(), err);
-                    continue;
-                }
-            };
+            visit(entry_meta, false);
+        }
 
-            content.push(entry_meta);
+        Ok(true)
+    }
+
+    /// Return why an entry is left out of the listing, if it is.
+    fn skip_reason(name: &OsStr, display: Display, ignore_globs: &GlobSet) -> Option<Skipped> {
+        if ignore_globs.is_match(name) {
+            return Some(Skipped::Ignored);
+        }
+
+        if let Display::DisplayOnlyVisible = display {
+            if name.to_string_lossy().starts_with('.') {
+                return Some(Skipped::Hidden);
+            }
+        }
+
+        None
+    }
+
+    /// Count the entries of the directory left out of the listing, returned
+    /// as the numbers of hidden and ignored entries.
+    pub fn count_skipped(
+        path: &Path,
+        display: Display,
+        ignore_globs: &GlobSet,
+    ) -> Result<(usize, usize), std::io::Error> {
+        let mut hidden = 0;
+        let mut ignored = 0;
+
+        for entry in path.read_dir()? {
+            match Self::skip_reason(&entry?.file_name(), display, ignore_globs) {
+                Some(Skipped::Hidden) => hidden += 1,
+                Some(Skipped::Ignored) => ignored += 1,
+                None => (),
+            }
+        }
+
+        Ok((hidden, ignored))
+    }
+
+    /// The timestamp of the entry selected by `--time`, if known.
+    pub fn time(&self, time: TimeFlag) -> Option<&Date> {
+        match time {
+            TimeFlag::Modified => Some(&self.date),
+            TimeFlag::Accessed => self.accessed.as_ref(),
+            TimeFlag::Changed => self.changed.as_ref(),
+            TimeFlag::Created => self.created.as_ref(),
         }
+    }
+
+    /// The size of the entry in bytes, measured as asked by `--size-kind`.
+    pub fn size_bytes(&self, kind: SizeKind) -> u64 {
+        match kind {
+            SizeKind::Apparent => self.size.get_bytes(),
+            SizeKind::Allocated => self.allocated_bytes(),
+        }
+    }
+
+    /// The space allocated to the entry in bytes, its length on the platforms
+    /// which don't report it.
+    pub fn allocated_bytes(&self) -> u64 {
+        self.blocks.allocated_bytes(self.size.get_bytes())
+    }
 
-        Ok(Some(content))
+    /// Whether the entry is a file with much less space allocated than its
+    /// length.
+    pub fn is_sparse(&self) -> bool {
+        match self.file_type {
+            FileType::File { .. } => self.blocks.is_sparse(self.size.get_bytes()),
+            _ => false,
+        }
     }
 
+    /// Replace the sizes of the directories by the total sizes of their
+    /// content: the apparent one in the size and the allocated one in the
+    /// blocks, so that `size_bytes` measures every entry the same way.
     pub fn calculate_total_size(&mut self) {
+        self.accumulate_total_size();
+    }
+
+    /// Replace the sizes of a directory by the total sizes of its content,
+    /// and return the apparent and allocated sizes of the entry.
+    fn accumulate_total_size(&mut self) -> (u64, u64) {
+        let mut total = (self.size.get_bytes(), self.allocated_bytes());
+
         if let FileType::Directory { .. } = self.file_type {
             if let Some(metas) = &mut self.content {
-                let mut size_accumulated = self.size.get_bytes();
                 for x in &mut metas.iter_mut() {
-                    x.calculate_total_size();
-                    size_accumulated += x.size.get_bytes();
+                    let (apparent, allocated) = x.accumulate_total_size();
+                    total.0 += apparent;
+                    total.1 += allocated;
                 }
-                self.size = Size::new(size_accumulated);
             } else {
                 // possibility that 'depth' limited the recursion in 'recurse_into'
-                self.size = Size::new(Meta::calculate_total_file_size(&self.path));
+                total = Meta::calculate_total_file_size(&self.path);
+            }
+
+            self.size = Size::new(total.0);
+            if self.blocks.count().is_some() {
+                self.blocks = Blocks::new(Some(total.1 / 512));
             }
         }
+
+        total
     }
 
-    fn calculate_total_file_size(path: &PathBuf) -> u64 {
+    /// Return the apparent and allocated sizes of the file or directory tree.
+    fn calculate_total_file_size(path: &PathBuf) -> (u64, u64) {
         let metadata = if read_link(&path).is_ok() {
             // If the file is a link, retrieve the metadata without following
             // the link.
@@ -154,14 +336,15 @@ impl Meta {
             Ok(meta) => meta,
             Err(err) => {
                 print_error!("lsd: {}: {}\n", path.display(), err);
-                return 0;
+                return (0, 0);
             }
         };
         let file_type = metadata.file_type();
+        let allocated = Blocks::from(&metadata).allocated_bytes(metadata.len());
         if file_type.is_file() {
-            metadata.len()
+            (metadata.len(), allocated)
         } else if file_type.is_dir() {
-            let mut size = metadata.len();
+            let mut size = (metadata.len(), allocated);
 
             let entries = match path.read_dir() {
                 Ok(entries) => entries,
@@ -178,11 +361,13 @@ impl Meta {
                         continue;
                     }
                 };
-                size += Meta::calculate_total_file_size(&path);
+                let (apparent, allocated) = Meta::calculate_total_file_size(&path);
+                size.0 += apparent;
+                size.1 += allocated;
             }
             size
         } else {
-            0
+            (0, 0)
         }
     }
 
@@ -212,13 +397,73 @@ impl Meta {
             path: path.to_path_buf(),
             symlink: SymLink::from(path),
             size: Size::from(&metadata),
+            blocks: Blocks::from(&metadata),
+            device: Device::from(&metadata),
+            links: Links::from(&metadata),
             date: Date::from(&metadata),
+            accessed: Date::accessed(&metadata),
+            changed: Date::changed(&metadata),
+            created: Date::created(&metadata),
             indicator: Indicator::from(file_type),
             owner,
             permissions,
+            attributes: Attributes::from(&metadata),
+            inode_flags: InodeFlags::default(),
+            xattrs: Xattrs::default(),
+            acl: Acl::default(),
+            context: Context::default(),
+            capabilities: Capabilities::default(),
             name,
             file_type,
             content: None,
         })
     }
+
+    /// Read the metadata which cost a system call per file, only when the
+    /// flags show them: `from_path` leaves them empty.
+    pub fn with_details(mut self, flags: &Flags) -> Self {
+        let path = self.path.as_path();
+        // The `+` and `@` indicators follow the permissions.
+        let permission = flags.blocks.contains(&Block::Permission);
+
+        if permission || flags.xattr {
+            self.xattrs = Xattrs::from(path);
+        }
+        if permission || flags.blocks.contains(&Block::Acl) {
+            self.acl = Acl::from(path);
+        }
+        if flags.blocks.contains(&Block::Context) {
+            self.context = Context::from(path);
+        }
+
+        // The permissions of the immutable files are highlighted. Opening the
+        // devices or the pipes to read their flags could block or have side
+        // effects.
+        let inode_flags = permission
+            || flags.blocks.contains(&Block::Attributes)
+            || flags.permission == PermissionFlag::Attributes;
+        if inode_flags
+            && matches!(
+                self.file_type,
+                FileType::File { .. } | FileType::Directory { .. }
+            )
+        {
+            self.inode_flags = InodeFlags::from(path);
+        }
+
+        // Only the executables are colored by their capabilities.
+        if let FileType::File { exec, .. } = self.file_type {
+            if flags.blocks.contains(&Block::Capabilities)
+                || (exec && flags.color != WhenFlag::Never)
+            {
+                self.capabilities = Capabilities::from(path);
+            }
+
+            if !self.capabilities.is_empty() {
+                self.name = self.name.with_capabilities();
+            }
+        }
+
+        self
+    }
 }
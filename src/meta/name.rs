@@ -1,6 +1,9 @@
 use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::{Flags, WhenFlag};
+use crate::hyperlink;
 use crate::icon::Icons;
 use crate::meta::filetype::FileType;
+use crate::quoting;
 use std::cmp::{Ordering, PartialOrd};
 use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
@@ -18,6 +21,7 @@ pub struct Name {
     path: PathBuf,
     extension: Option<String>,
     file_type: FileType,
+    capabilities: bool,
 }
 
 impl Name {
@@ -41,9 +45,17 @@ impl Name {
             path: PathBuf::from(path),
             extension,
             file_type,
+            capabilities: false,
         }
     }
 
+    /// Mark the name of a file with capabilities, colored like the setuid
+    /// files.
+    pub fn with_capabilities(mut self) -> Self {
+        self.capabilities = true;
+        self
+    }
+
     pub fn file_name(&self) -> &str {
         self.path
             .file_name()
@@ -80,34 +92,70 @@ impl Name {
             .collect()
     }
 
+    /// Return the name as shown with the display option: the file name or
+    /// a path.
+    pub fn display_text(&self, display_option: &DisplayOption) -> String {
+        match display_option {
+            DisplayOption::FileName => self.file_name().to_string(),
+            DisplayOption::Relative { base_path } => {
+                self.relative_path(base_path).to_string_lossy().to_string()
+            }
+            DisplayOption::None => self.path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// Return whether the name shown with the display option is wrapped in
+    /// quotes by the quoting style.
+    pub fn is_quoted(&self, display_option: &DisplayOption, flags: &Flags) -> bool {
+        quoting::needs_quotes(&self.display_text(display_option), flags.quoting_style())
+    }
+
+    /// Render the name, quoted following the quoting style. The unquoted
+    /// names are shifted by `quote_padding` spaces to stay aligned with the
+    /// quoted ones.
     pub fn render(
         &self,
         colors: &Colors,
         icons: &Icons,
         display_option: &DisplayOption,
+        flags: &Flags,
+        quote_padding: usize,
     ) -> ColoredString {
-        let content = match display_option {
-            DisplayOption::FileName => format!("{}{}", icons.get(self), self.file_name()),
-            DisplayOption::Relative { base_path } => format!(
-                "{}{}",
-                icons.get(self),
-                self.relative_path(base_path).to_string_lossy()
-            ),
-            DisplayOption::None => format!("{}{}", icons.get(self), self.path.to_string_lossy()),
+        let name = self.display_text(display_option);
+
+        let style = flags.quoting_style();
+        let padding = if quoting::needs_quotes(&name, style) {
+            0
+        } else {
+            quote_padding
         };
+        let mut name = quoting::quote(&name, style);
+        if flags.hyperlink == WhenFlag::Always {
+            name = hyperlink::wrap(&name, &self.path);
+        }
+        let content = format!("{}{}{}", icons.get(self), " ".repeat(padding), name);
 
         let elem = match self.file_type {
             FileType::CharDevice => Elem::CharDevice,
             FileType::Directory { uid } => Elem::Dir { uid },
             FileType::SymLink => Elem::SymLink,
-            FileType::File { uid, exec } => Elem::File { uid, exec },
+            FileType::File { uid, exec } => Elem::File {
+                uid: uid || self.capabilities,
+                exec,
+            },
             _ => Elem::File {
                 exec: false,
                 uid: false,
             },
         };
 
-        colors.colorize_using_path(content, &self.path, &elem)
+        // LS_COLORS can't tell the files with capabilities from the others,
+        // hence they always use the theme.
+        if self.capabilities {
+            colors.colorize(content, &elem)
+        } else {
+            colors.colorize_using_path(content, &self.path, &elem)
+        }
     }
 
     pub fn extension(&self) -> Option<&str> {
@@ -144,6 +192,7 @@ mod test {
     use super::DisplayOption;
     use super::Name;
     use crate::color::{self, Colors};
+    use crate::flags::{Flags, QuotingStyle};
     use crate::icon::{self, Icons};
     use crate::meta::FileType;
     use crate::meta::Meta;
@@ -176,7 +225,39 @@ mod test {
 
         assert_eq!(
             Colour::Fixed(11).paint(" file.txt"),
-            name.render(&colors, &icons, &DisplayOption::FileName)
+            name.render(
+                &colors,
+                &icons,
+                &DisplayOption::FileName,
+                &Flags::default(),
+                0
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_print_name_with_capabilities() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let icons = Icons::new(icon::Theme::NoIcon);
+
+        let file_path = tmp_dir.path().join("ping");
+        File::create(&file_path).expect("failed to create file");
+        let meta = file_path.metadata().expect("failed to get metas");
+
+        let colors = Colors::new(color::Theme::NoLscolors);
+        let file_type = FileType::new(&meta, &Permissions::from(&meta));
+        let name = Name::new(&file_path, file_type).with_capabilities();
+
+        assert_eq!(
+            Colour::Fixed(11).on(Colour::Fixed(124)).paint("ping"),
+            name.render(
+                &colors,
+                &icons,
+                &DisplayOption::FileName,
+                &Flags::default(),
+                0
+            )
         );
     }
 
@@ -194,7 +275,13 @@ mod test {
 
         assert_eq!(
             Colour::Fixed(4).paint(" directory"),
-            meta.name.render(&colors, &icons, &DisplayOption::FileName)
+            meta.name.render(
+                &colors,
+                &icons,
+                &DisplayOption::FileName,
+                &Flags::default(),
+                0
+            )
         );
     }
 
@@ -221,7 +308,13 @@ mod test {
 
         assert_eq!(
             Colour::Fixed(6).paint(" target.tmp"),
-            name.render(&colors, &icons, &DisplayOption::FileName)
+            name.render(
+                &colors,
+                &icons,
+                &DisplayOption::FileName,
+                &Flags::default(),
+                0
+            )
         );
     }
 
@@ -247,7 +340,13 @@ mod test {
 
         assert_eq!(
             Colour::Fixed(11).paint(" pipe.tmp"),
-            name.render(&colors, &icons, &DisplayOption::FileName)
+            name.render(
+                &colors,
+                &icons,
+                &DisplayOption::FileName,
+                &Flags::default(),
+                0
+            )
         );
     }
 
@@ -266,12 +365,48 @@ mod test {
         assert_eq!(
             "file.txt",
             meta.name
-                .render(&colors, &icons, &DisplayOption::FileName)
+                .render(
+                    &colors,
+                    &icons,
+                    &DisplayOption::FileName,
+                    &Flags::default(),
+                    0
+                )
                 .to_string()
                 .as_str()
         );
     }
 
+    #[test]
+    fn test_print_quoted_name() {
+        let icons = Icons::new(icon::Theme::NoIcon);
+        let colors = Colors::new(color::Theme::NoColor);
+        let flags = Flags {
+            quoting_style: Some(QuotingStyle::ShellEscape),
+            ..Flags::default()
+        };
+        let file_type = FileType::File {
+            exec: false,
+            uid: false,
+        };
+
+        let name = Name::new(Path::new("my file"), file_type);
+        assert!(name.is_quoted(&DisplayOption::FileName, &flags));
+        assert_eq!(
+            "'my file'",
+            name.render(&colors, &icons, &DisplayOption::FileName, &flags, 1)
+                .to_string()
+        );
+
+        let name = Name::new(Path::new("file"), file_type);
+        assert!(!name.is_quoted(&DisplayOption::FileName, &flags));
+        assert_eq!(
+            " file",
+            name.render(&colors, &icons, &DisplayOption::FileName, &flags, 1)
+                .to_string()
+        );
+    }
+
     #[test]
     fn test_extensions_with_valid_file() {
         let path = Path::new("some-file.txt");
@@ -1,4 +1,5 @@
 use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::Flags;
 #[cfg(unix)]
 use std::fs::Metadata;
 
@@ -6,12 +7,19 @@ use std::fs::Metadata;
 pub struct Owner {
     user: String,
     group: String,
+    uid: Option<u32>,
+    gid: Option<u32>,
 }
 
 impl Owner {
     #[cfg_attr(unix, allow(dead_code))]
     pub fn new(user: String, group: String) -> Self {
-        Self { user, group }
+        Self {
+            user,
+            group,
+            uid: None,
+            gid: None,
+        }
     }
 }
 
@@ -31,16 +39,92 @@ impl<'a> From<&'a Metadata> for Owner {
             None => meta.gid().to_string(),
         };
 
-        Self { user, group }
+        Self {
+            user,
+            group,
+            uid: Some(meta.uid()),
+            gid: Some(meta.gid()),
+        }
     }
 }
 
 impl Owner {
-    pub fn render_user(&self, colors: &Colors) -> ColoredString {
-        colors.colorize(self.user.clone(), &Elem::User)
+    pub fn user(&self) -> &str {
+        &self.user
     }
 
-    pub fn render_group(&self, colors: &Colors) -> ColoredString {
-        colors.colorize(self.group.clone(), &Elem::Group)
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    pub fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    pub fn gid(&self) -> Option<u32> {
+        self.gid
+    }
+
+    /// Return the user name, or the user ID with `--numeric-uid-gid`.
+    pub fn user_string(&self, flags: &Flags) -> String {
+        match (flags.numeric_ids, self.uid) {
+            (true, Some(uid)) => uid.to_string(),
+            _ => self.user.clone(),
+        }
+    }
+
+    /// Return the group name, or the group ID with `--numeric-uid-gid`.
+    pub fn group_string(&self, flags: &Flags) -> String {
+        match (flags.numeric_ids, self.gid) {
+            (true, Some(gid)) => gid.to_string(),
+            _ => self.group.clone(),
+        }
+    }
+
+    pub fn render_user(&self, colors: &Colors, flags: &Flags) -> ColoredString {
+        colors.colorize(self.user_string(flags), &Elem::User)
+    }
+
+    pub fn render_group(&self, colors: &Colors, flags: &Flags) -> ColoredString {
+        colors.colorize(self.group_string(flags), &Elem::Group)
+    }
+
+    pub fn render_uid(&self, colors: &Colors) -> ColoredString<'_> {
+        colors.colorize(id_string(self.uid), &Elem::User)
+    }
+
+    pub fn render_gid(&self, colors: &Colors) -> ColoredString<'_> {
+        colors.colorize(id_string(self.gid), &Elem::Group)
+    }
+}
+
+fn id_string(id: Option<u32>) -> String {
+    match id {
+        Some(id) => id.to_string(),
+        None => String::from("-"),
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::Owner;
+    use crate::flags::Flags;
+    use std::os::unix::fs::MetadataExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_numeric_ids() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let metadata = tmp_dir.path().metadata().unwrap();
+        let owner = Owner::from(&metadata);
+
+        let flags = Flags {
+            numeric_ids: true,
+            ..Flags::default()
+        };
+        assert_eq!(metadata.uid().to_string(), owner.user_string(&flags));
+        assert_eq!(metadata.gid().to_string(), owner.group_string(&flags));
+        assert_eq!(Some(metadata.uid()), owner.uid());
     }
 }
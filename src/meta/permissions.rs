@@ -98,6 +98,33 @@ impl Permissions {
         ColoredString::from(res)
     }
 
+    /// Return the permission bits, e.g. `0o4755` for `rwsr-xr-x`.
+    pub fn bits(&self) -> u32 {
+        let bit = |set: bool, value: u32| if set { value } else { 0 };
+
+        bit(self.setuid, 0o4000)
+            | bit(self.setgid, 0o2000)
+            | bit(self.sticky, 0o1000)
+            | bit(self.user_read, 0o400)
+            | bit(self.user_write, 0o200)
+            | bit(self.user_execute, 0o100)
+            | bit(self.group_read, 0o040)
+            | bit(self.group_write, 0o020)
+            | bit(self.group_execute, 0o010)
+            | bit(self.other_read, 0o004)
+            | bit(self.other_write, 0o002)
+            | bit(self.other_execute, 0o001)
+    }
+
+    pub fn render_octal(&self, colors: &Colors) -> ColoredString<'_> {
+        colors.colorize(self.octal_string(), &Elem::Octal)
+    }
+
+    /// Return the permission bits in octal, e.g. `0755`.
+    pub fn octal_string(&self) -> String {
+        format!("{:04o}", self.bits())
+    }
+
     pub fn is_executable(&self) -> bool {
         self.user_execute || self.group_execute || self.other_execute
     }
@@ -127,3 +154,30 @@ mod modes {
     pub const SETGID: Mode = libc::S_ISGID as Mode;
     pub const SETUID: Mode = libc::S_ISUID as Mode;
 }
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::Permissions;
+    use std::fs::{self, File};
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_octal_string() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file.txt");
+        File::create(&file_path).expect("failed to create file");
+
+        for mode in &[0o644, 0o755, 0o4750, 0o1777] {
+            fs::set_permissions(&file_path, fs::Permissions::from_mode(*mode))
+                .expect("failed to set permissions");
+            let meta = file_path.metadata().expect("failed to get metas");
+
+            assert_eq!(
+                format!("{:04o}", mode),
+                Permissions::from(&meta).octal_string()
+            );
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::{Flags, WhenFlag};
+use crate::{hyperlink, quoting};
 use ansi_term::{ANSIString, ANSIStrings};
 use std::fs::read_link;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug)]
 pub struct SymLink {
     target: Option<String>,
+    /// The path of the target, joined to the directory of the link.
+    path: Option<PathBuf>,
     valid: bool,
 }
 
@@ -15,6 +19,7 @@ impl<'a> From<&'a Path> for SymLink {
             if target.is_absolute() || path.parent() == None {
                 return Self {
                     valid: target.exists(),
+                    path: Some(target.clone()),
                     target: Some(
                         target
                             .to_str()
@@ -31,17 +36,26 @@ impl<'a> From<&'a Path> for SymLink {
                         .expect("failed to convert symlink to str")
                         .to_string(),
                 ),
+                path: Some(path.parent().unwrap().join(&target)),
                 valid: path.parent().unwrap().join(target).exists(),
             };
         }
 
         Self {
             target: None,
+            path: None,
             valid: false,
         }
     }
 }
 
+/// Return the canonical form of the path, or its absolute form when it
+/// doesn't exist.
+fn resolve(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .unwrap_or_else(|_| hyperlink::absolute_path(path))
+}
+
 impl SymLink {
     pub fn symlink_string(&self) -> Option<String> {
         if let Some(ref target) = self.target {
@@ -51,7 +65,24 @@ impl SymLink {
         }
     }
 
-    pub fn render(&self, colors: &Colors) -> ColoredString {
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn render_target(&self, target: &str, flags: &Flags) -> String {
+        let target = quoting::quote(target, flags.quoting_style());
+
+        // Resolving the target walks its whole path, so it is only done for
+        // the hyperlinks.
+        match &self.path {
+            Some(path) if flags.hyperlink == WhenFlag::Always => {
+                hyperlink::wrap(&target, &resolve(path))
+            }
+            _ => target,
+        }
+    }
+
+    pub fn render(&self, colors: &Colors, flags: &Flags) -> ColoredString {
         if let Some(target_string) = self.symlink_string() {
             let elem = if self.valid {
                 &Elem::SymLink
@@ -61,7 +92,7 @@ impl SymLink {
 
             let strings: &[ColoredString] = &[
                 ColoredString::from(" \u{21d2} "), // ⇒
-                colors.colorize(target_string, elem),
+                colors.colorize(self.render_target(&target_string, flags), elem),
             ];
 
             let res = ANSIStrings(strings).to_string();
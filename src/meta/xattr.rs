@@ -0,0 +1,323 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::path::Path;
+
+/// An extended attribute: its name and the size of its value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Xattr {
+    pub name: String,
+    pub size: usize,
+}
+
+/// The extended attributes of a file, read without following the symlinks.
+/// The ones storing the POSIX ACLs are left out, as the ACLs are shown on
+/// their own.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Xattrs {
+    attrs: Vec<Xattr>,
+}
+
+const ACL_PREFIX: &[u8] = b"system.posix_acl_";
+
+impl From<&Path> for Xattrs {
+    fn from(path: &Path) -> Self {
+        let attrs = sys::list(path)
+            .into_iter()
+            .filter(|name| !name.starts_with(ACL_PREFIX))
+            .map(|name| {
+                let size = sys::value_size(path, &name).unwrap_or(0);
+                Xattr {
+                    name: String::from_utf8_lossy(&name).to_string(),
+                    size,
+                }
+            })
+            .collect();
+
+        Self { attrs }
+    }
+}
+
+impl Xattrs {
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+
+    pub fn attrs(&self) -> &[Xattr] {
+        &self.attrs
+    }
+
+    /// Render the `@` shown after the permissions of the files which have
+    /// extended attributes.
+    pub fn render_indicator(&self, colors: &Colors) -> ColoredString<'_> {
+        if self.is_empty() {
+            ColoredString::from("")
+        } else {
+            colors.colorize(String::from("@"), &Elem::Xattr)
+        }
+    }
+}
+
+/// Read the value of an extended attribute, without following the symlinks.
+pub fn get(path: &Path, name: &str) -> Option<Vec<u8>> {
+    sys::value(path, name.as_bytes())
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sys {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+
+    fn c_path(path: &Path) -> Option<CString> {
+        CString::new(path.as_os_str().as_bytes()).ok()
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn listxattr(path: &CString, buf: *mut libc::c_char, size: usize) -> isize {
+        libc::llistxattr(path.as_ptr(), buf, size)
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn listxattr(path: &CString, buf: *mut libc::c_char, size: usize) -> isize {
+        libc::listxattr(path.as_ptr(), buf, size, libc::XATTR_NOFOLLOW)
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn getxattr(
+        path: &CString,
+        name: &CString,
+        buf: *mut libc::c_void,
+        size: usize,
+    ) -> isize {
+        libc::lgetxattr(path.as_ptr(), name.as_ptr(), buf, size)
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn getxattr(
+        path: &CString,
+        name: &CString,
+        buf: *mut libc::c_void,
+        size: usize,
+    ) -> isize {
+        libc::getxattr(
+            path.as_ptr(),
+            name.as_ptr(),
+            buf,
+            size,
+            0,
+            libc::XATTR_NOFOLLOW,
+        )
+    }
+
+    /// Return the names of the attributes. The filesystems which don't
+    /// support them have none.
+    pub fn list(path: &Path) -> Vec<Vec<u8>> {
+        let path = match c_path(path) {
+            Some(path) => path,
+            None => return vec![],
+        };
+
+        let size = unsafe { listxattr(&path, ptr::null_mut(), 0) };
+        if size <= 0 {
+            return vec![];
+        }
+
+        let mut buffer = vec![0u8; size as usize];
+        let size = unsafe {
+            listxattr(
+                &path,
+                buffer.as_mut_ptr() as *mut libc::c_char,
+                buffer.len(),
+            )
+        };
+        if size <= 0 {
+            return vec![];
+        }
+        buffer.truncate(size as usize);
+
+        buffer
+            .split(|&byte| byte == 0)
+            .filter(|name| !name.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+
+    pub fn value_size(path: &Path, name: &[u8]) -> Option<usize> {
+        let path = c_path(path)?;
+        let name = CString::new(name).ok()?;
+
+        let size = unsafe { getxattr(&path, &name, ptr::null_mut(), 0) };
+        if size < 0 {
+            None
+        } else {
+            Some(size as usize)
+        }
+    }
+
+    /// Read the value in a single call when it fits in a small buffer, asking
+    /// for its size only when it doesn't.
+    pub fn value(path: &Path, name: &[u8]) -> Option<Vec<u8>> {
+        let path = c_path(path)?;
+        let name = CString::new(name).ok()?;
+
+        let mut buffer = vec![0u8; 256];
+        loop {
+            let size = unsafe {
+                getxattr(
+                    &path,
+                    &name,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            if size >= 0 {
+                buffer.truncate(size as usize);
+                return Some(buffer);
+            }
+            if io::Error::last_os_error().raw_os_error() != Some(libc::ERANGE) {
+                return None;
+            }
+
+            // The value may still grow before the next read, hence the loop.
+            let size = unsafe { getxattr(&path, &name, ptr::null_mut(), 0) };
+            if size < 0 {
+                return None;
+            }
+            buffer = vec![0u8; size as usize];
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod sys {
+    use std::path::Path;
+
+    pub fn list(_: &Path) -> Vec<Vec<u8>> {
+        vec![]
+    }
+
+    pub fn value_size(_: &Path, _: &[u8]) -> Option<usize> {
+        None
+    }
+
+    pub fn value(_: &Path, _: &[u8]) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::{get, Xattr, Xattrs};
+    use crate::flags::Flags;
+    use crate::meta::Meta;
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::os::unix::ffi::OsStrExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_xattrs_of_plain_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+
+        // Some systems add security attributes to every file.
+        let xattrs = Xattrs::from(path.as_path());
+        assert!(xattrs
+            .attrs()
+            .iter()
+            .all(|attr| !attr.name.starts_with("user.")));
+    }
+
+    #[test]
+    fn test_xattrs_of_file_with_attributes() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+
+        // The user attributes are not supported by every filesystem.
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let c_name = CString::new("user.comment").unwrap();
+        let result = unsafe {
+            libc::setxattr(
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                b"hello".as_ptr() as *const libc::c_void,
+                5,
+                0,
+            )
+        };
+        if result != 0 {
+            return;
+        }
+
+        // They are only read when shown.
+        let meta = Meta::from_path(&path).unwrap();
+        assert!(meta
+            .clone()
+            .with_details(&Flags::default())
+            .xattrs
+            .is_empty());
+        let flags = Flags {
+            xattr: true,
+            ..Flags::default()
+        };
+        assert!(!meta.with_details(&flags).xattrs.is_empty());
+
+        let xattrs = Xattrs::from(path.as_path());
+        assert_eq!(
+            &[Xattr {
+                name: "user.comment".to_string(),
+                size: 5
+            }],
+            xattrs.attrs()
+        );
+        assert_eq!(Some(b"hello".to_vec()), get(&path, "user.comment"));
+    }
+
+    #[test]
+    fn test_xattrs_leave_out_acls() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+
+        // The ACL `u::rw-,u:4242:rw-,g::r--,m::rw-,o::r--`.
+        let mut value = 2u32.to_le_bytes().to_vec();
+        for (tag, perm, id) in &[
+            (0x01u16, 6u16, u32::MAX),
+            (0x02, 6, 4242),
+            (0x04, 4, u32::MAX),
+            (0x10, 6, u32::MAX),
+            (0x20, 4, u32::MAX),
+        ] {
+            value.extend_from_slice(&tag.to_le_bytes());
+            value.extend_from_slice(&perm.to_le_bytes());
+            value.extend_from_slice(&id.to_le_bytes());
+        }
+
+        // The ACLs are not supported by every filesystem.
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let c_name = CString::new("system.posix_acl_access").unwrap();
+        let result = unsafe {
+            libc::setxattr(
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                value.as_ptr() as *const libc::c_void,
+                value.len(),
+                0,
+            )
+        };
+        if result != 0 {
+            return;
+        }
+
+        let xattrs = Xattrs::from(path.as_path());
+        assert!(xattrs
+            .attrs()
+            .iter()
+            .all(|attr| !attr.name.starts_with("system.posix_acl_")));
+        assert!(get(&path, "system.posix_acl_access").is_some());
+    }
+}
@@ -0,0 +1,166 @@
+use crate::flags::QuotingStyle;
+
+/// The characters which have a meaning for the shell, anywhere in a word.
+const SHELL_SPECIAL_CHARS: &[char] = &[
+    ' ', '\t', '\n', '"', '$', '&', '\'', '(', ')', '*', ';', '<', '>', '?', '[', '\\', ']', '^',
+    '`', '{', '|', '}', '!',
+];
+
+/// Return whether the name is wrapped in quotes by the quoting style.
+pub fn needs_quotes(name: &str, style: QuotingStyle) -> bool {
+    match style {
+        QuotingStyle::Literal | QuotingStyle::Escape => false,
+        QuotingStyle::ShellAlways | QuotingStyle::C => true,
+        QuotingStyle::Shell | QuotingStyle::ShellEscape => {
+            name.is_empty()
+                || name.starts_with(&['#', '~'][..])
+                || name.contains(SHELL_SPECIAL_CHARS)
+                || name.contains(char::is_control)
+        }
+    }
+}
+
+/// Quote the name following the `--quoting-style` semantics of coreutils.
+pub fn quote(name: &str, style: QuotingStyle) -> String {
+    match style {
+        QuotingStyle::Literal => name.to_string(),
+        QuotingStyle::Shell | QuotingStyle::ShellAlways => {
+            // The control characters can't be written safely without the
+            // `$'...'` syntax, so hide them as with `ls`.
+            let name: String = name
+                .chars()
+                .map(|c| if c.is_control() { '?' } else { c })
+                .collect();
+
+            if needs_quotes(&name, style) {
+                shell_quote(&name)
+            } else {
+                name
+            }
+        }
+        QuotingStyle::ShellEscape => {
+            if !name.contains(char::is_control) {
+                return quote(name, QuotingStyle::Shell);
+            }
+
+            // Quote the printable parts, and write the control characters
+            // with the `$'...'` syntax understood by bash, zsh and ksh.
+            let mut output = String::new();
+            let mut part = String::new();
+            for c in name.chars() {
+                if c.is_control() {
+                    if !part.is_empty() {
+                        output += &shell_quote(&part);
+                        part.clear();
+                    }
+                    output += &format!("$'{}'", c_escape(&c.to_string(), false));
+                } else {
+                    part.push(c);
+                }
+            }
+            if !part.is_empty() {
+                output += &shell_quote(&part);
+            }
+
+            output
+        }
+        QuotingStyle::C => format!("\"{}\"", c_escape(name, true)),
+        QuotingStyle::Escape => c_escape(name, false).replace(' ', "\\ "),
+    }
+}
+
+/// Wrap the string in single quotes, or in double quotes when it only
+/// contains single quotes which need no escaping there.
+fn shell_quote(input: &str) -> String {
+    if !input.contains('\'') {
+        format!("'{}'", input)
+    } else if !input.contains(&['"', '$', '`', '\\', '!'][..]) {
+        format!("\"{}\"", input)
+    } else {
+        format!("'{}'", input.replace('\'', "'\\''"))
+    }
+}
+
+/// Escape the backslashes and the control characters as in a C string.
+fn c_escape(input: &str, escape_double_quote: bool) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '"' if escape_double_quote => output.push_str("\\\""),
+            '\u{7}' => output.push_str("\\a"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{b}' => output.push_str("\\v"),
+            c if c.is_control() && (c as u32) < 0x100 => {
+                output.push_str(&format!("\\{:03o}", c as u32))
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::{needs_quotes, quote};
+    use crate::flags::QuotingStyle;
+
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!("a b", quote("a b", QuotingStyle::Literal));
+        assert_eq!("a\nb", quote("a\nb", QuotingStyle::Literal));
+    }
+
+    #[test]
+    fn test_quote_shell() {
+        assert_eq!("file.txt", quote("file.txt", QuotingStyle::Shell));
+        assert_eq!("'a b'", quote("a b", QuotingStyle::Shell));
+        assert_eq!("'#tmp'", quote("#tmp", QuotingStyle::Shell));
+        assert_eq!("a#b", quote("a#b", QuotingStyle::Shell));
+        assert_eq!("\"it's\"", quote("it's", QuotingStyle::Shell));
+        assert_eq!("'it'\\''s $HOME'", quote("it's $HOME", QuotingStyle::Shell));
+        assert_eq!("'a?b'", quote("a\nb", QuotingStyle::Shell));
+        assert_eq!("''", quote("", QuotingStyle::Shell));
+    }
+
+    #[test]
+    fn test_quote_shell_always() {
+        assert_eq!("'file.txt'", quote("file.txt", QuotingStyle::ShellAlways));
+        assert_eq!("'a b'", quote("a b", QuotingStyle::ShellAlways));
+    }
+
+    #[test]
+    fn test_quote_shell_escape() {
+        assert_eq!("file.txt", quote("file.txt", QuotingStyle::ShellEscape));
+        assert_eq!("'a b'", quote("a b", QuotingStyle::ShellEscape));
+        assert_eq!("'a'$'\\n''b'", quote("a\nb", QuotingStyle::ShellEscape));
+        assert_eq!("$'\\001'", quote("\u{1}", QuotingStyle::ShellEscape));
+    }
+
+    #[test]
+    fn test_quote_c() {
+        assert_eq!("\"file.txt\"", quote("file.txt", QuotingStyle::C));
+        assert_eq!("\"a \\\"b\\\"\\t\"", quote("a \"b\"\t", QuotingStyle::C));
+        assert_eq!("\"\\\\\\033\"", quote("\\\u{1b}", QuotingStyle::C));
+    }
+
+    #[test]
+    fn test_quote_escape() {
+        assert_eq!("a\\ b\\n", quote("a b\n", QuotingStyle::Escape));
+        assert_eq!("\"a\"", quote("\"a\"", QuotingStyle::Escape));
+    }
+
+    #[test]
+    fn test_needs_quotes() {
+        assert!(!needs_quotes("file.txt", QuotingStyle::ShellEscape));
+        assert!(needs_quotes("a b", QuotingStyle::ShellEscape));
+        assert!(needs_quotes("file.txt", QuotingStyle::C));
+        assert!(!needs_quotes("a b", QuotingStyle::Escape));
+    }
+}
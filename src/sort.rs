@@ -19,14 +19,21 @@ pub fn by_meta(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
             DirOrderFlag::None => by_date(a, b, &flags),
             DirOrderFlag::Last => by_date_with_files_first(a, b, &flags),
         },
+        SortFlag::Links => match flags.directory_order {
+            DirOrderFlag::First => by_links_with_dirs_first(a, b, flags),
+            DirOrderFlag::None => by_links(a, b, flags),
+            DirOrderFlag::Last => by_links_with_files_first(a, b, flags),
+        },
     }
 }
 
 fn by_size(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
     if flags.sort_order == SortOrder::Default {
-        b.size.get_bytes().cmp(&a.size.get_bytes())
+        b.size_bytes(flags.size_kind)
+            .cmp(&a.size_bytes(flags.size_kind))
     } else {
-        a.size.get_bytes().cmp(&b.size.get_bytes())
+        a.size_bytes(flags.size_kind)
+            .cmp(&b.size_bytes(flags.size_kind))
     }
 }
 
@@ -76,9 +83,13 @@ fn by_name_with_files_first(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
 
 fn by_date(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
     if flags.sort_order == SortOrder::Default {
-        b.date.cmp(&a.date).then(a.name.cmp(&b.name))
+        b.time(flags.time)
+            .cmp(&a.time(flags.time))
+            .then(a.name.cmp(&b.name))
     } else {
-        a.date.cmp(&b.date).then(b.name.cmp(&a.name))
+        a.time(flags.time)
+            .cmp(&b.time(flags.time))
+            .then(b.name.cmp(&a.name))
     }
 }
 
@@ -100,6 +111,38 @@ fn by_date_with_files_first(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
     }
 }
 
+fn by_links(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
+    if flags.sort_order == SortOrder::Default {
+        b.links
+            .nlink()
+            .cmp(&a.links.nlink())
+            .then(a.name.cmp(&b.name))
+    } else {
+        a.links
+            .nlink()
+            .cmp(&b.links.nlink())
+            .then(b.name.cmp(&a.name))
+    }
+}
+
+fn by_links_with_dirs_first(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
+    match (a.file_type, b.file_type) {
+        (FileType::Directory { .. }, FileType::Directory { .. }) => by_links(a, b, flags),
+        (FileType::Directory { .. }, _) => Ordering::Less,
+        (_, FileType::Directory { .. }) => Ordering::Greater,
+        _ => by_links(a, b, flags),
+    }
+}
+
+fn by_links_with_files_first(a: &Meta, b: &Meta, flags: &Flags) -> Ordering {
+    match (a.file_type, b.file_type) {
+        (FileType::Directory { .. }, FileType::Directory { .. }) => by_links(a, b, flags),
+        (FileType::Directory { .. }, _) => Ordering::Greater,
+        (_, FileType::Directory { .. }) => Ordering::Less,
+        _ => by_links(a, b, flags),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -252,4 +295,33 @@ mod tests {
         flags.sort_order = SortOrder::Reverse;
         assert_eq!(by_meta(&meta_a, &meta_z, &flags), Ordering::Greater);
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_sort_by_meta_by_links() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        // Create the file;
+        let path_a = tmp_dir.path().join("aaa");
+        File::create(&path_a).expect("failed to create file");
+        let meta_a = Meta::from_path(&path_a).expect("failed to get meta");
+
+        // Create the file with a second link;
+        let path_z = tmp_dir.path().join("zzz");
+        File::create(&path_z).expect("failed to create file");
+        std::fs::hard_link(&path_z, tmp_dir.path().join("link")).expect("failed to create link");
+        let meta_z = Meta::from_path(&path_z).expect("failed to get meta");
+
+        let mut flags = Flags {
+            sort_by: SortFlag::Links,
+            ..Flags::default()
+        };
+
+        // Sort by links
+        assert_eq!(by_meta(&meta_a, &meta_z, &flags), Ordering::Greater);
+
+        // Sort by links reversed
+        flags.sort_order = SortOrder::Reverse;
+        assert_eq!(by_meta(&meta_a, &meta_z, &flags), Ordering::Less);
+    }
 }
@@ -0,0 +1,177 @@
+use crate::flags::Flags;
+use crate::meta::{FileType, Meta, Size};
+use crate::print_error;
+use std::path::{Component, Path};
+
+/// The counts and the total size of the listed entries, shown by `--summary`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Summary {
+    files: usize,
+    dirs: usize,
+    symlinks: usize,
+    /// The total size of the entries which are not directories.
+    bytes: u64,
+    hidden: usize,
+    ignored: usize,
+}
+
+impl Summary {
+    /// Summarize the content listed for the directory, counting the entries
+    /// which were left out as hidden or ignored.
+    pub fn from_content(path: &Path, content: &[Meta], flags: &Flags) -> Self {
+        let mut summary = Self::default();
+
+        for meta in content {
+            // The implied `.` and `..` entries are not part of the content.
+            if meta.name.name != "." && !meta.path.ends_with(Component::ParentDir) {
+                summary.add_entry(meta);
+            }
+        }
+
+        match Meta::count_skipped(path, flags.display, &flags.ignore_globs) {
+            Ok((hidden, ignored)) => {
+                summary.hidden = hidden;
+                summary.ignored = ignored;
+            }
+            Err(err) => {
+                print_error!("lsd: {}: {}\n", path.display(), err);
+            }
+        }
+
+        summary
+    }
+
+    /// Summarize everything listed: the inputs which are not listed through
+    /// their content, and the content of every listed directory.
+    pub fn total(metas: &[Meta], flags: &Flags) -> Self {
+        let mut summary = Self::default();
+
+        for meta in metas {
+            match &meta.content {
+                Some(content) => summary.add_listing(meta, content, flags),
+                None => summary.add_entry(meta),
+            }
+        }
+
+        summary
+    }
+
+    fn add_listing(&mut self, meta: &Meta, content: &[Meta], flags: &Flags) {
+        self.add(&Self::from_content(&meta.path, content, flags));
+
+        for entry in content {
+            if let Some(content) = &entry.content {
+                self.add_listing(entry, content, flags);
+            }
+        }
+    }
+
+    fn add_entry(&mut self, meta: &Meta) {
+        match meta.file_type {
+            FileType::Directory { .. } => self.dirs += 1,
+            FileType::SymLink => {
+                self.symlinks += 1;
+                self.bytes += meta.size.get_bytes();
+            }
+            _ => {
+                self.files += 1;
+                self.bytes += meta.size.get_bytes();
+            }
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.files += other.files;
+        self.dirs += other.dirs;
+        self.symlinks += other.symlinks;
+        self.bytes += other.bytes;
+        self.hidden += other.hidden;
+        self.ignored += other.ignored;
+    }
+
+    pub fn render(&self, flags: &Flags) -> String {
+        let size = Size::new(self.bytes);
+
+        format!(
+            "{}, {}, {}, {} {} ({} hidden, {} ignored)\n",
+            count(self.files, "file", "files"),
+            count(self.dirs, "directory", "directories"),
+            count(self.symlinks, "symlink", "symlinks"),
+            size.value_string(flags),
+            size.unit_string(flags),
+            self.hidden,
+            self.ignored
+        )
+    }
+}
+
+fn count(number: usize, singular: &str, plural: &str) -> String {
+    if number == 1 {
+        format!("{} {}", number, singular)
+    } else {
+        format!("{} {}", number, plural)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Summary;
+    use crate::flags::{Display, Flags};
+    use crate::meta::Meta;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_summary_from_content() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let mut file = File::create(tmp_dir.path().join("file")).expect("failed to create file");
+        file.write_all(b"content").expect("failed to write file");
+        File::create(tmp_dir.path().join(".hidden")).expect("failed to create file");
+        fs::create_dir(tmp_dir.path().join("dir")).expect("failed to create dir");
+
+        let flags = Flags::default();
+        let meta = Meta::from_path(tmp_dir.path()).unwrap();
+        let content = meta.recurse_into(1, &flags).unwrap().unwrap();
+
+        assert_eq!(
+            "1 file, 1 directory, 0 symlinks, 7 B (1 hidden, 0 ignored)\n",
+            Summary::from_content(tmp_dir.path(), &content, &flags).render(&flags)
+        );
+    }
+
+    #[test]
+    fn test_summary_skips_implied_entries() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        File::create(tmp_dir.path().join(".hidden")).expect("failed to create file");
+
+        let flags = Flags {
+            display: Display::DisplayAll,
+            ..Flags::default()
+        };
+        let meta = Meta::from_path(tmp_dir.path()).unwrap();
+        let content = meta.recurse_into(1, &flags).unwrap().unwrap();
+
+        assert_eq!(
+            "1 file, 0 directories, 0 symlinks, 0 B (0 hidden, 0 ignored)\n",
+            Summary::from_content(tmp_dir.path(), &content, &flags).render(&flags)
+        );
+    }
+
+    #[test]
+    fn test_summary_total() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::create_dir(tmp_dir.path().join("dir")).expect("failed to create dir");
+        File::create(tmp_dir.path().join("dir/one")).expect("failed to create file");
+        File::create(tmp_dir.path().join("two")).expect("failed to create file");
+
+        let flags = Flags::default();
+        let mut meta = Meta::from_path(tmp_dir.path()).unwrap();
+        meta.content = meta.recurse_into(2, &flags).unwrap();
+
+        assert_eq!(
+            "2 files, 1 directory, 0 symlinks, 0 B (0 hidden, 0 ignored)\n",
+            Summary::total(&[meta], &flags).render(&flags)
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use crate::color::Elem;
+use crate::config_file::find_config_file;
+use ansi_term::{Colour, Style};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// A color theme read from a YAML file.
+///
+/// Each key of the file names an `Elem` and maps it to a style. The elements
+/// missing from the file keep the colors of the default theme.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColorTheme {
+    styles: HashMap<Elem, Style>,
+}
+
+/// Icon mappings read from a YAML file, added to (or replacing) the built-in
+/// ones.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct IconSet {
+    /// Icons by exact file name.
+    #[serde(default)]
+    pub name: HashMap<String, String>,
+    /// Icons by file extension, without the leading dot.
+    #[serde(default)]
+    pub extension: HashMap<String, String>,
+    /// Icons by exact directory name.
+    #[serde(default)]
+    pub dir_name: HashMap<String, String>,
+    /// Icons by file type. The `file` and `dir` entries replace the default
+    /// file and folder icons.
+    #[serde(default)]
+    pub file_type: HashMap<FileTypeKey, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileTypeKey {
+    File,
+    Dir,
+    Symlink,
+    Pipe,
+    Socket,
+    CharDevice,
+    BlockDevice,
+    Special,
+}
+
+/// A style, written either as a lone color or as a map of attributes.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StyleSpec {
+    Colour(ColourSpec),
+    Full {
+        #[serde(default)]
+        foreground: Option<ColourSpec>,
+        #[serde(default)]
+        background: Option<ColourSpec>,
+        #[serde(default)]
+        bold: bool,
+        #[serde(default)]
+        italic: bool,
+        #[serde(default)]
+        underline: bool,
+    },
+}
+
+/// A color, written either as a 256-color index, as a name, or as a
+/// `#rrggbb` truecolor value.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ColourSpec {
+    Fixed(u8),
+    Named(String),
+}
+
+impl ColorTheme {
+    /// Load the theme `<name>.yaml` from the `themes` folder of the lsd
+    /// configuration directory.
+    pub fn from_name(name: &str) -> Result<Self, Error> {
+        let path = ["yaml", "yml"]
+            .iter()
+            .find_map(|ext| find_config_file(&format!("themes/{}.{}", name, ext)))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("color theme \"{}\" not found", name),
+                )
+            })?;
+
+        Self::from_file(&path)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        Self::from_yaml(&fs::read_to_string(path)?)
+    }
+
+    fn from_yaml(content: &str) -> Result<Self, Error> {
+        // An empty YAML document is a valid theme using only default colors.
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let specs: HashMap<String, StyleSpec> =
+            serde_yaml::from_str(content).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+        let mut styles = HashMap::new();
+        for (key, spec) in specs {
+            let elem = elem_from_key(&key).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unknown theme element \"{}\"", key),
+                )
+            })?;
+
+            styles.insert(elem, spec.to_style()?);
+        }
+
+        Ok(Self { styles })
+    }
+
+    pub fn styles(&self) -> &HashMap<Elem, Style> {
+        &self.styles
+    }
+}
+
+impl IconSet {
+    /// Load the icon set `icons.yaml` from the lsd configuration directory,
+    /// if there is one.
+    pub fn from_config_dir() -> Result<Option<Self>, Error> {
+        match find_config_file("icons.yaml").or_else(|| find_config_file("icons.yml")) {
+            Some(path) => Self::from_file(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        Self::from_yaml(&fs::read_to_string(path)?)
+    }
+
+    fn from_yaml(content: &str) -> Result<Self, Error> {
+        // An empty YAML document is a valid set keeping every default icon.
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(content).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
+
+impl StyleSpec {
+    fn to_style(&self) -> Result<Style, Error> {
+        match self {
+            StyleSpec::Colour(colour) => Ok(Style::default().fg(colour.to_colour()?)),
+            StyleSpec::Full {
+                foreground,
+                background,
+                bold,
+                italic,
+                underline,
+            } => {
+                let mut style = Style::default();
+                if let Some(colour) = foreground {
+                    style = style.fg(colour.to_colour()?);
+                }
+                if let Some(colour) = background {
+                    style = style.on(colour.to_colour()?);
+                }
+                style.is_bold = *bold;
+                style.is_italic = *italic;
+                style.is_underline = *underline;
+
+                Ok(style)
+            }
+        }
+    }
+}
+
+impl ColourSpec {
+    fn to_colour(&self) -> Result<Colour, Error> {
+        let name = match self {
+            ColourSpec::Fixed(index) => return Ok(Colour::Fixed(*index)),
+            ColourSpec::Named(name) => name.to_lowercase(),
+        };
+
+        let colour = match name.as_str() {
+            "black" => Colour::Black,
+            "red" => Colour::Red,
+            "green" => Colour::Green,
+            "yellow" => Colour::Yellow,
+            "blue" => Colour::Blue,
+            "purple" | "magenta" => Colour::Purple,
+            "cyan" => Colour::Cyan,
+            "white" => Colour::White,
+            // Check the digits first: the slices below are byte ranges.
+            hex if hex.len() == 7
+                && hex.starts_with('#')
+                && hex[1..].chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                let component = |range| u8::from_str_radix(&hex[range], 16);
+                match (component(1..3), component(3..5), component(5..7)) {
+                    (Ok(r), Ok(g), Ok(b)) => Colour::RGB(r, g, b),
+                    _ => return Err(invalid_colour(&name)),
+                }
+            }
+            _ => return Err(invalid_colour(&name)),
+        };
+
+        Ok(colour)
+    }
+}
+
+fn invalid_colour(name: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid color \"{}\"", name),
+    )
+}
+
+fn elem_from_key(key: &str) -> Option<Elem> {
+    let elem = match key {
+        // Node type
+        "file" => Elem::File {
+            exec: false,
+            uid: false,
+        },
+        "file-exec" => Elem::File {
+            exec: true,
+            uid: false,
+        },
+        "file-uid" => Elem::File {
+            exec: false,
+            uid: true,
+        },
+        "file-exec-uid" => Elem::File {
+            exec: true,
+            uid: true,
+        },
+        "dir" => Elem::Dir { uid: false },
+        "dir-uid" => Elem::Dir { uid: true },
+        "symlink" => Elem::SymLink,
+        "broken-symlink" => Elem::BrokenSymLink,
+        "pipe" => Elem::Pipe,
+        "block-device" => Elem::BlockDevice,
+        "char-device" => Elem::CharDevice,
+        "socket" => Elem::Socket,
+        "special" => Elem::Special,
+
+        // Permissions
+        "read" => Elem::Read,
+        "write" => Elem::Write,
+        "exec" => Elem::Exec,
+        "exec-sticky" => Elem::ExecSticky,
+        "no-access" => Elem::NoAccess,
+
+        // Last Time Modified
+        "hour-old" => Elem::HourOld,
+        "day-old" => Elem::DayOld,
+        "older" => Elem::Older,
+
+        // User / Group Name
+        "user" => Elem::User,
+        "group" => Elem::Group,
+
+        // File Size
+        "non-file" => Elem::NonFile,
+        "file-small" => Elem::FileSmall,
+        "file-medium" => Elem::FileMedium,
+        "file-large" => Elem::FileLarge,
+
+        // INode
+        "inode-valid" => Elem::INode { valid: true },
+        "inode-invalid" => Elem::INode { valid: false },
+        "octal" => Elem::Octal,
+        "xattr" => Elem::Xattr,
+        "acl" => Elem::Acl,
+        "context" => Elem::Context,
+        "immutable" => Elem::Immutable,
+        // Hard link count
+        "links" => Elem::Links { multiple: false },
+        "links-multiple" => Elem::Links { multiple: true },
+
+        // Column titles
+        "header" => Elem::Header,
+
+        _ => return None,
+    };
+
+    Some(elem)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColorTheme, FileTypeKey, IconSet};
+    use crate::color::Elem;
+    use ansi_term::{Colour, Style};
+
+    #[test]
+    fn test_read_theme() {
+        let theme = ColorTheme::from_yaml(
+            r##"
+user: 230
+group: "#ff8000"
+dir: blue
+file-large:
+  foreground: red
+  background: 236
+  bold: true
+  underline: true
+"##,
+        )
+        .unwrap();
+        let styles = theme.styles();
+
+        assert_eq!(
+            Some(&Style::default().fg(Colour::Fixed(230))),
+            styles.get(&Elem::User)
+        );
+        assert_eq!(
+            Some(&Style::default().fg(Colour::RGB(255, 128, 0))),
+            styles.get(&Elem::Group)
+        );
+        assert_eq!(
+            Some(&Style::default().fg(Colour::Blue)),
+            styles.get(&Elem::Dir { uid: false })
+        );
+        assert_eq!(
+            Some(
+                &Style::default()
+                    .fg(Colour::Red)
+                    .on(Colour::Fixed(236))
+                    .bold()
+                    .underline()
+            ),
+            styles.get(&Elem::FileLarge)
+        );
+        assert_eq!(None, styles.get(&Elem::Older));
+    }
+
+    #[test]
+    fn test_read_invalid_theme() {
+        assert!(ColorTheme::from_yaml("unknown: 1").is_err());
+        assert!(ColorTheme::from_yaml("user: \"#12345\"").is_err());
+        assert!(ColorTheme::from_yaml("user: \"#é1234\"").is_err());
+        assert!(ColorTheme::from_yaml("user: \"#1é234\"").is_err());
+        assert!(ColorTheme::from_yaml("user: rainbow").is_err());
+    }
+
+    #[test]
+    fn test_read_icon_set() {
+        let set = IconSet::from_yaml(
+            r#"
+extension:
+  proto: "P"
+dir-name:
+  .git: "G"
+file-type:
+  file: "F"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(Some(&"P".to_string()), set.extension.get("proto"));
+        assert_eq!(Some(&"G".to_string()), set.dir_name.get(".git"));
+        assert_eq!(
+            Some(&"F".to_string()),
+            set.file_type.get(&FileTypeKey::File)
+        );
+        assert!(set.name.is_empty());
+    }
+
+    #[test]
+    fn test_read_invalid_icon_set() {
+        assert!(IconSet::from_yaml("file-type:\n  folder: \"F\"").is_err());
+        assert!(IconSet::from_yaml("icons: {}").is_err());
+    }
+}
@@ -157,6 +157,458 @@ fn test_list_broken_link_ok() {
         .stderr(predicate::str::contains(matched).not());
 }
 
+#[test]
+fn test_list_with_config_file() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    dir.child(".hidden").touch().unwrap();
+
+    let config = tempdir();
+    let config_file = config.child("config.yaml");
+    config_file.write_str("display: almost-all\n").unwrap();
+
+    cmd()
+        .arg("--config-file")
+        .arg(config_file.path())
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::is_match("\\.hidden\none\n$").unwrap());
+
+    cmd()
+        .arg("--ignore-config")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::is_match("^one\n$").unwrap());
+}
+
+#[test]
+fn test_list_json_format() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    dir.child("two").touch().unwrap();
+
+    cmd()
+        .arg("--format")
+        .arg("json")
+        .arg("--blocks")
+        .arg("size,name")
+        .arg(dir.path())
+        .assert()
+        .stdout(
+            predicate::str::contains("\"name\": \"one\"")
+                .and(predicate::str::contains("\"name\": \"two\""))
+                .and(predicate::str::contains("\"size\": 0"))
+                .and(predicate::str::contains("\"user\"").not()),
+        );
+}
+
+#[test]
+fn test_list_ndjson_format_recursive() {
+    let dir = tempdir();
+    dir.child("one").create_dir_all().unwrap();
+    dir.child("one/two").touch().unwrap();
+
+    cmd()
+        .arg("--format")
+        .arg("ndjson")
+        .arg("--recursive")
+        .arg(dir.path())
+        .assert()
+        .stdout(
+            predicate::str::is_match("(?m)^\\{\"name\":\"one\",.*\"depth\":1,")
+                .unwrap()
+                .and(
+                    predicate::str::is_match(
+                        "(?m)^\\{\"name\":\"two\",.*\"depth\":2,\"parent\":\".*one\"\\}$",
+                    )
+                    .unwrap(),
+                ),
+        );
+}
+
+#[test]
+fn test_list_print0() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    dir.child("two\nlines").touch().unwrap();
+
+    cmd()
+        .arg("--print0")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("one\0two\nlines\0"));
+}
+
+#[test]
+fn test_list_print0_recursive() {
+    let dir = tempdir();
+    dir.child("one").create_dir_all().unwrap();
+    dir.child("one/two").touch().unwrap();
+
+    let one = dir.path().join("one");
+    let two = one.join("two");
+    cmd()
+        .arg("-0")
+        .arg("--recursive")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::similar(format!(
+            "{}\0{}\0",
+            one.display(),
+            two.display()
+        )));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_print0_symlink_with_indicators() {
+    let dir = tempdir();
+    dir.child("a.txt").touch().unwrap();
+    dir.child("sub").create_dir_all().unwrap();
+    fs::symlink("a.txt", dir.path().join("link")).unwrap();
+
+    // Only the names, to be read back by `xargs -0`.
+    cmd()
+        .arg("-0")
+        .arg("-F")
+        .arg("--inode")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("a.txt\0link\0sub\0"));
+}
+
+#[test]
+fn test_list_commas() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    dir.child("two").touch().unwrap();
+
+    cmd()
+        .arg("-m")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("one, two\n"));
+}
+
+#[test]
+fn test_list_header() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+
+    cmd()
+        .arg("--header")
+        .arg("--blocks")
+        .arg("size,name")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("Size Name\n0 B  one\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_links() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    dir.child("two").touch().unwrap();
+    std::fs::hard_link(dir.path().join("two"), dir.path().join("three")).unwrap();
+
+    cmd()
+        .arg("--blocks")
+        .arg("links,name")
+        .arg("--linksort")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("2 three\n2 two\n1 one\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_disk_usage_of_sparse_file() {
+    let dir = tempdir();
+    let file = std::fs::File::create(dir.path().join("sparse")).unwrap();
+    file.set_len(1024 * 1024).unwrap();
+
+    cmd()
+        .arg("--blocks")
+        .arg("disk-usage,name")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("0 B~ sparse\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_total_size_keeps_apparent_and_allocated_sizes() {
+    let dir = tempdir();
+    dir.child("sub").create_dir_all().unwrap();
+    let file = std::fs::File::create(dir.path().join("sub/sparse")).unwrap();
+    file.set_len(1024 * 1024).unwrap();
+
+    // The size is the apparent one, whatever the sizes are sorted by.
+    cmd()
+        .arg("--blocks")
+        .arg("size,name")
+        .arg("--total-size")
+        .arg("--size-kind")
+        .arg("allocated")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("1 MB sub\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sort_by_allocated_size() {
+    let dir = tempdir();
+    dir.child("full").write_binary(&[1; 8192]).unwrap();
+    let file = std::fs::File::create(dir.path().join("sparse")).unwrap();
+    file.set_len(1024 * 1024).unwrap();
+
+    cmd()
+        .arg("--sizesort")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("sparse\nfull\n"));
+
+    cmd()
+        .arg("--sizesort")
+        .arg("--size-kind")
+        .arg("allocated")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("full\nsparse\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sort_by_access_time() {
+    let dir = tempdir();
+    dir.child("old").touch().unwrap();
+    dir.child("new").touch().unwrap();
+    for (name, date) in &[("old", "202001010000"), ("new", "202101010000")] {
+        let success = std::process::Command::new("touch")
+            .arg("-a")
+            .arg("-t")
+            .arg(date)
+            .arg(dir.path().join(name))
+            .status()
+            .unwrap()
+            .success();
+        assert!(success, "failed to exec touch");
+    }
+
+    cmd()
+        .arg("--timesort")
+        .arg("--time")
+        .arg("accessed")
+        .arg("--date")
+        .arg("+%Y")
+        .arg("--blocks")
+        .arg("date,name")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("2021 new\n2020 old\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_octal_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir();
+    dir.child("script").touch().unwrap();
+    std::fs::set_permissions(
+        dir.path().join("script"),
+        std::fs::Permissions::from_mode(0o4755),
+    )
+    .unwrap();
+
+    cmd()
+        .arg("--blocks")
+        .arg("permission,octal,name")
+        .arg("--permission")
+        .arg("octal")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq(".4755 4755 script\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_attributes_of_symlink() {
+    let dir = tempdir();
+    dir.child("target").touch().unwrap();
+    let link = dir.path().join("link");
+    fs::symlink("target", &link).unwrap();
+
+    // The inode flags of the symlinks are never read.
+    cmd()
+        .arg("--blocks")
+        .arg("attributes,name")
+        .arg(&link)
+        .assert()
+        .stdout(predicate::str::starts_with("- "));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_list_capabilities() {
+    let dir = tempdir();
+    dir.child("ping").touch().unwrap();
+    dir.child("plain").touch().unwrap();
+
+    // Setting the capabilities needs privileges, like `setcap` does.
+    let value: Vec<u8> = [0x0200_0001u32, 1 << 13, 0, 0, 0]
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    if !set_xattr(&dir.path().join("ping"), "security.capability", &value) {
+        return;
+    }
+
+    cmd()
+        .arg("--blocks")
+        .arg("capabilities,name")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq("cap_net_raw+ep ping\n-              plain\n"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_list_xattrs_in_long_view() {
+    let dir = tempdir();
+    dir.child("a\nb").touch().unwrap();
+    dir.child("c").touch().unwrap();
+
+    // The user attributes are not supported by every filesystem.
+    for name in &["a\nb", "c"] {
+        if !set_xattr(&dir.path().join(name), "user.comment", b"hello") {
+            return;
+        }
+    }
+
+    // The attributes stay under their entry, even below a name on two lines.
+    cmd()
+        .arg("--oneline")
+        .arg("--xattr")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::eq(
+            "a\nb\n\u{2514}\u{2500}\u{2500} user.comment (5 B)\n\
+             c\n\u{2514}\u{2500}\u{2500} user.comment (5 B)\n",
+        ));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_list_xattrs_in_tree() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    dir.child("two").touch().unwrap();
+
+    if !set_xattr(&dir.path().join("one"), "user.comment", b"hello") {
+        return;
+    }
+
+    cmd()
+        .arg("--tree")
+        .arg("--xattr")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::similar(format!(
+            "{}\n\
+             \u{251c}\u{2500}\u{2500} one\n\
+             \u{2502}     \u{2514}\u{2500}\u{2500} user.comment (5 B)\n\
+             \u{2514}\u{2500}\u{2500} two\n",
+            dir.path().file_name().unwrap().to_string_lossy()
+        )));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_list_context() {
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+
+    // The files have a label only on the systems running SELinux.
+    let label = |path: &std::path::Path| match get_xattr(path, "security.selinux") {
+        Some(value) => {
+            String::from_utf8_lossy(value.strip_suffix(&[0]).unwrap_or(&value)).to_string()
+        }
+        None => "?".to_string(),
+    };
+    let file_label = label(&dir.path().join("one"));
+
+    cmd()
+        .arg("-Z")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::similar(format!("{} one\n", file_label)));
+
+    cmd()
+        .arg("-Z")
+        .arg("--tree")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::similar(format!(
+            "{} {}\n\u{2514}\u{2500}\u{2500} {} one\n",
+            label(dir.path()),
+            dir.path().file_name().unwrap().to_string_lossy(),
+            file_label
+        )));
+
+    cmd()
+        .arg("-lZ")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::ends_with(format!(" {} one\n", file_label)));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_numeric_ids() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = tempdir();
+    dir.child("one").touch().unwrap();
+    let metadata = dir.path().join("one").metadata().unwrap();
+
+    cmd()
+        .arg("--numeric-uid-gid")
+        .arg("--no-total")
+        .arg("--blocks")
+        .arg("user,group,uid,name")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::similar(format!(
+            "{} {} {} one\n",
+            metadata.uid(),
+            metadata.gid(),
+            metadata.uid()
+        )));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_long_block_total() {
+    let dir = tempdir();
+    dir.child("one").write_binary(&[1; 8192]).unwrap();
+
+    cmd()
+        .arg("-l")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::is_match("^total [1-9][0-9]*\n").unwrap());
+
+    cmd()
+        .arg("-l")
+        .arg("--no-total")
+        .arg(dir.path())
+        .assert()
+        .stdout(predicate::str::contains("total").not());
+}
+
 fn cmd() -> Command {
     Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
 }
@@ -164,3 +616,50 @@ fn cmd() -> Command {
 fn tempdir() -> assert_fs::TempDir {
     assert_fs::TempDir::new().unwrap()
 }
+
+/// Set an extended attribute of the file, returning false when the
+/// filesystem doesn't support it or it needs privileges.
+#[cfg(target_os = "linux")]
+fn set_xattr(path: &std::path::Path, name: &str, value: &[u8]) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    let name = CString::new(name).unwrap();
+    let result = unsafe {
+        libc::setxattr(
+            path.as_ptr(),
+            name.as_ptr(),
+            value.as_ptr() as *const libc::c_void,
+            value.len(),
+            0,
+        )
+    };
+
+    result == 0
+}
+
+/// Read an extended attribute of the file, without following the symlinks.
+#[cfg(target_os = "linux")]
+fn get_xattr(path: &std::path::Path, name: &str) -> Option<Vec<u8>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    let name = CString::new(name).unwrap();
+    let mut buffer = vec![0u8; 256];
+    let size = unsafe {
+        libc::lgetxattr(
+            path.as_ptr(),
+            name.as_ptr(),
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            buffer.len(),
+        )
+    };
+    if size < 0 {
+        return None;
+    }
+    buffer.truncate(size as usize);
+
+    Some(buffer)
+}